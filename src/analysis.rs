@@ -7,15 +7,62 @@
 //!     It compares the value of specified [`Board`](`crate::Board`) to a given [`BoardValue`].
 //! - [`create_checkmate_tree`] and [`create_checkmate_tree_with_value`]<br>
 //!     It creates an [`BoardValueTree`] that describes routes to ends of the game.
+//! - [`create_forcing_tree`]<br>
+//!     It proves a long forced win quickly by searching only actions creating threats.
 //! - [`find_best_actions`]<br>
 //!     It collects the best [`Action`](`crate::Action`)s by [`BoardValue`].
+//! - [`evaluate_board_with_pv`]<br>
+//!     It calculates the value together with one [`PrincipalVariation`],
+//!     a line of actions of both players under optimal play.
+//!
+//! [`compare_board_value`], [`evaluate_board`] and [`find_best_actions`]
+//! have variants with suffix "_with_table", which receive a [`TranspositionTable`]
+//! to reuse results of searches,
+//! and variants with suffix "_parallel", which search with multiple threads.
+//! [`create_checkmate_tree`], [`evaluate_board`] and [`find_best_actions`]
+//! also have variants with suffix "_with_limit", which deepen the search gradually
+//! until a [`SearchLimit`] (a deadline or a cancellation flag) is reached
+//! and return the result of the deepest completed search.
+//! [`evaluate_board`] and [`find_best_actions`] have more flexible variants
+//! with suffix "_with_options", which receive [`SearchOptions`]
+//! including a callback to observe the progress,
+//! and they report [`SearchStats`] such as the number of visited nodes.
+//!
+//! When the value of a board is not determined by searching,
+//! [`score_actions`] and [`find_best_actions_with_evaluator`] rank actions
+//! by an alpha-beta search with a heuristic [`Evaluator`] such as [`DefaultEvaluator`].
+//!
+//! A [`Tablebase`] stores values of boards with a few doves obtained by retrograde analysis.
+//! It can be consulted by [`evaluate_board_with_tablebase`] and [`find_best_actions_with_tablebase`].
+//! [`PositionEnumerator`] enumerates all positions reachable from the initial board
+//! ply by ply.
+//! [`perft`], [`perft_divide`] and [`perft_checked`] count action sequences
+//! to test the generation of actions.
 //!
 //! The [`BoardValue`] struct plays important roles in the all above functions.
 //! See their documentations for more.
 
 pub(crate) mod board_value;
+pub(crate) mod enumeration;
+pub(crate) mod forcing;
+pub(crate) mod heuristic;
+pub(crate) mod limit;
+pub(crate) mod parallel;
+pub(crate) mod perft;
+pub(crate) mod tablebase;
+pub(crate) mod transposition;
+pub(crate) mod variation;
 
 // By-pass export
 pub use crate::prelude::board::canonicalizer::PositionMapper;
 
 pub use board_value::*;
+pub use enumeration::*;
+pub use forcing::*;
+pub use heuristic::*;
+pub use limit::*;
+pub use parallel::*;
+pub use perft::*;
+pub use tablebase::*;
+pub use transposition::*;
+pub use variation::*;
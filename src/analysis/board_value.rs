@@ -1,11 +1,18 @@
+use std::cell::Cell;
 use std::cmp::{Ord, Ordering, PartialOrd};
 use std::collections::{hash_map, HashMap};
 use std::io::{BufWriter, Write};
+use std::ops::ControlFlow;
+use std::sync::{
+    atomic::{AtomicBool, Ordering as AtomicOrdering},
+    Mutex,
+};
 
 use crate::{
+    analysis::{SearchLimit, SearchStats, Tablebase, TranspositionTable},
     error,
     game::{GameRule, Judge},
-    Action, ActionsFwdIntoIter, Board, BoardBuilder, Color, SurroundedStatus,
+    Action, ActionContainer, ActionsFwd, Board, BoardBuilder, Color, SurroundedStatus,
 };
 
 // ****************************************************************************
@@ -15,9 +22,11 @@ use crate::{
 ///
 /// This type is returned by [`BoardValue::kind`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
 pub enum BoardValueKind {
     Win,
     Lose,
+    Draw,
     Unknown,
     Finished,
 }
@@ -35,12 +44,17 @@ impl std::fmt::Display for BoardValueKind {
 }
 
 impl PartialOrd for BoardValueKind {
+    /// `Draw` is less than `Win` and greater than `Lose`,
+    /// but it is not compared to `Unknown`.
+    /// The order is consistent with that of [`BoardValue`],
+    /// where `Draw(n)` is greater than `Unknown` if n is odd
+    /// and less than `Unknown` if n is even.
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         fn _kind_to_u8(kind: &BoardValueKind) -> u8 {
             use BoardValueKind::*;
             match kind {
                 Win => 2,
-                Unknown => 1,
+                Unknown | Draw => 1,
                 Lose => 0,
                 Finished => unreachable!(),
             }
@@ -54,6 +68,9 @@ impl PartialOrd for BoardValueKind {
                 return None;
             }
         }
+        if matches!((self, other), (Draw, Unknown) | (Unknown, Draw)) {
+            return None;
+        }
 
         _kind_to_u8(self).partial_cmp(&_kind_to_u8(other))
     }
@@ -64,13 +81,21 @@ impl PartialOrd for BoardValueKind {
 /// The order of the values is as follows:
 /// ```text
 /// BoardValue::MIN = Lose(2) < Lose(4) < Lose(6) < ...
+///     < Draw(2) < Draw(4) < Draw(6) < ...
 ///     < Unknown
+///     < ... < Draw(5) < Draw(3) < Draw(1)
 ///     < ... < Win(5) < Win(3) < Win(1) = BoardValue::MAX
 /// ```
 /// - n of `Lose(n)` means that the player will lose in n turns at most.
 /// - n of `Win(n)` means that the player will win in n turns at least.
+/// - n of `Draw(n)` means that the game will end in a draw in n turns.
+///     It appears only when `suicide_atk_judge` of [`GameRule`] is `Judge::Draw`.
+///     If n is odd, the player is the one who makes the game drawn,
+///     and if n is even, the opponent is.
+///     Every player prefers a draw made by themselves to an unknown result
+///     and an unknown result to a draw made by the opponent.
 /// - `Unknown` means that the value of the board was failed to be determined.
-///     It can change to `Lose(n)` or `Win(n)` if you search more deeply.
+///     It can change to `Lose(n)`, `Win(n)` or `Draw(n)` if you search more deeply.
 ///
 /// It takes another value `Finished`, which is attributed to boards of finished games.
 /// This value is, however, not compared to other values,
@@ -78,6 +103,7 @@ impl PartialOrd for BoardValueKind {
 #[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
 pub struct BoardValue {
     value: Option<usize>,
+    is_draw: bool,
 }
 
 impl std::fmt::Debug for BoardValue {
@@ -101,6 +127,39 @@ impl std::fmt::Display for BoardValue {
     }
 }
 
+/// Serializes the value as its string expression, e.g. `"Win(3)"` or `"Unknown"`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for BoardValue {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for BoardValue {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+        let s = String::deserialize(deserializer)?;
+        let value = match s.as_str() {
+            "Unknown" => Some(BoardValue::unknown()),
+            "Finished" => Some(BoardValue::finished()),
+            _ => s
+                .strip_suffix(')')
+                .and_then(|s| s.split_once('('))
+                .and_then(|(kind, num)| {
+                    let num = num.parse().ok()?;
+                    match kind {
+                        "Win" => BoardValue::win(num),
+                        "Lose" => BoardValue::lose(num),
+                        "Draw" => BoardValue::draw(num),
+                        _ => None,
+                    }
+                }),
+        };
+        value.ok_or_else(|| D::Error::custom(format!("invalid board value: {s}")))
+    }
+}
+
 impl BoardValue {
     /// Win(1)
     ///
@@ -109,7 +168,10 @@ impl BoardValue {
     /// use tokyodoves::analysis::BoardValue;
     /// assert_eq!(BoardValue::MAX, BoardValue::win(1).unwrap());
     /// ```
-    pub const MAX: BoardValue = BoardValue { value: Some(1) };
+    pub const MAX: BoardValue = BoardValue {
+        value: Some(1),
+        is_draw: false,
+    };
     /// Lose(2)
     ///
     /// # Examples
@@ -117,7 +179,10 @@ impl BoardValue {
     /// use tokyodoves::analysis::BoardValue;
     /// assert_eq!(BoardValue::MIN, BoardValue::lose(2).unwrap());
     /// ```
-    pub const MIN: BoardValue = BoardValue { value: Some(2) };
+    pub const MIN: BoardValue = BoardValue {
+        value: Some(2),
+        is_draw: false,
+    };
 
     /// Returns the kind of the value.
     ///
@@ -133,6 +198,7 @@ impl BoardValue {
         match self.value {
             None => Unknown,
             Some(0) => Finished,
+            Some(_) if self.is_draw => Draw,
             Some(n) => match n % 2 {
                 0 => Lose,
                 1 => Win,
@@ -154,7 +220,7 @@ impl BoardValue {
     /// ```
     pub fn win(num: usize) -> Option<Self> {
         if num % 2 == 1 {
-            Some(BoardValue { value: Some(num) })
+            Some(BoardValue::from(Some(num)))
         } else {
             None
         }
@@ -174,7 +240,29 @@ impl BoardValue {
     /// ```
     pub fn lose(num: usize) -> Option<Self> {
         if num != 0 && num % 2 == 0 {
-            Some(BoardValue { value: Some(num) })
+            Some(BoardValue::from(Some(num)))
+        } else {
+            None
+        }
+    }
+
+    /// Creates draw value.
+    ///
+    /// The argument `num` must be positive, otherwise it returns `None`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tokyodoves::analysis::{BoardValue, BoardValueKind};
+    ///
+    /// let value = BoardValue::draw(2).unwrap();
+    /// assert_eq!(value.kind(), BoardValueKind::Draw);
+    /// ```
+    pub fn draw(num: usize) -> Option<Self> {
+        if num != 0 {
+            Some(BoardValue {
+                value: Some(num),
+                is_draw: true,
+            })
         } else {
             None
         }
@@ -190,7 +278,7 @@ impl BoardValue {
     /// assert_eq!(value.kind(), BoardValueKind::Unknown);
     /// ```
     pub fn unknown() -> Self {
-        BoardValue { value: None }
+        BoardValue::from(None)
     }
 
     /// Creates finished value.
@@ -203,12 +291,12 @@ impl BoardValue {
     /// assert_eq!(value.kind(), BoardValueKind::Finished);
     /// ```
     pub fn finished() -> Self {
-        BoardValue { value: Some(0) }
+        BoardValue::from(Some(0))
     }
 
-    /// Tries to get the number in win or lose.
+    /// Tries to get the number in win, lose or draw.
     ///
-    /// If `self` is not win or lose, it returns `None`.
+    /// If `self` is not win, lose or draw, it returns `None`.
     ///
     /// # Examples
     /// ```
@@ -226,10 +314,10 @@ impl BoardValue {
         }
     }
 
-    /// Get the number in win or lose.
+    /// Get the number in win, lose or draw.
     ///
     /// # Panics
-    /// Panics if `self` is not win or lose.
+    /// Panics if `self` is not win, lose or draw.
     ///
     /// # Examples
     /// ```
@@ -272,6 +360,21 @@ impl BoardValue {
         matches!(self.kind(), BoardValueKind::Lose)
     }
 
+    /// Returns `true` if `self` is draw.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tokyodoves::analysis::BoardValue;
+    ///
+    /// let draw = BoardValue::draw(1).unwrap();
+    /// assert!(draw.is_draw());
+    /// let unknown = BoardValue::unknown();
+    /// assert!(!unknown.is_draw());
+    /// ```
+    pub fn is_draw(&self) -> bool {
+        matches!(self.kind(), BoardValueKind::Draw)
+    }
+
     /// Returns `true` if `self` is unknown.
     ///
     /// # Examples
@@ -306,6 +409,7 @@ impl BoardValue {
     /// ```text
     /// Unknown -> Unknown
     /// Finished -> Win(1) -> Lose(2) -> Win(3) -> Lose(4) -> ...
+    /// Draw(1) -> Draw(2) -> Draw(3) -> Draw(4) -> ...
     /// ```
     ///
     /// # Examples
@@ -321,6 +425,7 @@ impl BoardValue {
     pub fn increment(&self) -> Self {
         Self {
             value: self.value.map(|num| num + 1),
+            ..*self
         }
     }
 
@@ -328,6 +433,7 @@ impl BoardValue {
     /// ```text
     /// Unknown -> Unknown
     /// ... -> Lose(4) -> Win(3) -> Lose(2) -> Win(1) -> Finished
+    /// ... -> Draw(4) -> Draw(3) -> Draw(2) -> Draw(1) -> Finished
     /// ```
     /// It returns `None` if self is `Finished`, otherwise `Some(next_value)`.
     /// # Examples
@@ -341,17 +447,36 @@ impl BoardValue {
     /// assert_eq!(Some(unknown), unknown.try_decrement());
     /// ```
     pub fn try_decrement(&self) -> Option<Self> {
-        let value = match self.value {
-            Some(0) => return None,
-            x => x.map(|num| num - 1),
-        };
-        Some(Self { value })
+        match self.value {
+            Some(0) => None,
+            Some(1) => Some(Self::finished()),
+            x => Some(Self {
+                value: x.map(|num| num - 1),
+                ..*self
+            }),
+        }
+    }
+
+    /// Returns a key which is ordered in the same way as values except `Finished`.
+    fn order_key(&self) -> (u8, usize) {
+        use BoardValueKind::*;
+        match (self.kind(), self.value) {
+            (Lose, Some(n)) => (0, n),
+            (Draw, Some(n)) if n % 2 == 0 => (1, n),
+            (Unknown, _) => (2, 0),
+            (Draw, Some(n)) => (3, usize::MAX - n),
+            (Win, Some(n)) => (4, usize::MAX - n),
+            _ => unreachable!(),
+        }
     }
 }
 
 impl From<Option<usize>> for BoardValue {
     fn from(value: Option<usize>) -> Self {
-        Self { value }
+        Self {
+            value,
+            is_draw: false,
+        }
     }
 }
 
@@ -363,23 +488,14 @@ impl From<BoardValue> for Option<usize> {
 
 impl PartialOrd for BoardValue {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        let left_kind = self.kind();
-        let right_kind = other.kind();
-
-        use BoardValueKind::*;
-        if (left_kind != right_kind) || matches!(left_kind, Unknown | Finished) {
-            return left_kind.partial_cmp(&right_kind);
-        }
-
-        // In the following, left_kind == right_kind not in {Unknown, Finished}
-
-        let left_num = self.value.as_ref().unwrap();
-        let right_num = other.value.as_ref().unwrap();
-        match left_kind {
-            Lose => left_num.partial_cmp(right_num),
-            Win => right_num.partial_cmp(left_num),
-            _ => unreachable!(),
+        if self.is_finished() || other.is_finished() {
+            return if self == other {
+                Some(Ordering::Equal)
+            } else {
+                None
+            };
         }
+        Some(self.order_key().cmp(&other.order_key()))
     }
 }
 
@@ -417,6 +533,49 @@ mod tests_board_value {
         }
     }
 
+    #[test]
+    fn test_create_draw() {
+        assert!(BoardValue::draw(0).is_none());
+        for n in 1..100 {
+            let val = BoardValue::draw(n).unwrap();
+            assert_ne!(val, BoardValue::from(Some(n)));
+            assert!(val.is_draw());
+            assert_eq!(val.kind(), BoardValueKind::Draw);
+            assert_eq!(val.unwrap(), n);
+            assert_eq!(val.increment(), BoardValue::draw(n + 1).unwrap());
+            if n == 1 {
+                assert_eq!(val.try_decrement(), Some(BoardValue::finished()));
+            } else {
+                assert_eq!(val.try_decrement(), BoardValue::draw(n - 1));
+            }
+        }
+    }
+
+    #[test]
+    fn test_kind_order_consistency() {
+        let values = [
+            BoardValue::lose(2).unwrap(),
+            BoardValue::lose(4).unwrap(),
+            BoardValue::draw(2).unwrap(),
+            BoardValue::unknown(),
+            BoardValue::draw(1).unwrap(),
+            BoardValue::draw(3).unwrap(),
+            BoardValue::win(3).unwrap(),
+            BoardValue::win(1).unwrap(),
+        ];
+        for a in values {
+            for b in values {
+                if a.kind() == b.kind() {
+                    continue;
+                }
+                // The order of kinds never contradicts that of values
+                if let Some(cmp) = a.kind().partial_cmp(&b.kind()) {
+                    assert_eq!(a.partial_cmp(&b), Some(cmp), "{a} {b}");
+                }
+            }
+        }
+    }
+
     #[test]
     fn test_create_unknown() {
         let val = BoardValue::unknown();
@@ -510,69 +669,137 @@ mod tests_board_value {
             is_first_loop = false;
         }
     }
+
+    #[test]
+    fn test_compare_draw() {
+        let unknown = BoardValue::unknown();
+        for num in 1..100 {
+            let draw = BoardValue::draw(num).unwrap();
+            assert!(BoardValue::MIN < draw);
+            assert!(draw < BoardValue::MAX);
+            assert!(BoardValue::lose(100).unwrap() < draw);
+            assert!(draw < BoardValue::win(99).unwrap());
+            if num % 2 == 1 {
+                assert!(unknown < draw);
+            } else {
+                assert!(draw < unknown);
+            }
+            assert!(draw.partial_cmp(&BoardValue::finished()).is_none());
+
+            // The order is reversed by increment like win and lose
+            for other_num in 1..100 {
+                let other = BoardValue::draw(other_num).unwrap();
+                assert_eq!(
+                    draw.partial_cmp(&other),
+                    other.increment().partial_cmp(&draw.increment())
+                );
+            }
+        }
+    }
 }
 
 // ****************************************************************************
-//  NextBoardIter
+//  NextBoards
 // ****************************************************************************
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum NextBoardStatus {
+pub(crate) enum NextBoardStatus {
     Win,
     Lose,
+    Draw,
     Unknown,
 }
 
-struct NextBoardIter {
-    current_board: Board,
-    current_player: Color,
-    wins_if_both: bool,
-    actions_iter: ActionsFwdIntoIter,
-}
-
-impl NextBoardIter {
-    fn new(current_board: Board, current_player: Color, rule: GameRule) -> Self {
-        use Judge::*;
-        let wins_if_both = match rule.suicide_atk_judge() {
-            LastWins => true,
-            NextWins => false,
-            Draw => panic!("validation error"),
-        };
-        let actions_iter = current_board
-            .legal_actions(current_player, true, true, *rule.is_remove_accepted())
-            .into_iter();
-        Self {
-            current_board,
-            current_player,
-            wins_if_both,
-            actions_iter,
+impl NextBoardStatus {
+    /// Returns the status of `next_board` after an action of `player`.
+    pub(crate) fn of(next_board: &Board, player: Color, rule: &GameRule) -> Self {
+        use NextBoardStatus::*;
+        match next_board.surrounded_status() {
+            SurroundedStatus::Both => match rule.suicide_atk_judge() {
+                Judge::LastWins => Win,
+                Judge::NextWins => Lose,
+                Judge::Draw => Draw,
+            },
+            SurroundedStatus::OneSide(p) if p == player => Lose,
+            SurroundedStatus::OneSide(_) => Win,
+            SurroundedStatus::None => Unknown,
         }
     }
 }
 
-impl Iterator for NextBoardIter {
-    type Item = (Action, Board, NextBoardStatus);
-    fn next(&mut self) -> Option<Self::Item> {
-        let action = self.actions_iter.next()?;
-        let next_board = self.current_board.perform_unchecked_copied(action);
+/// Performs each legal action of `player` on `board` by [`Board::make`]
+/// and calls `f` with the action, the resulting board and its status.
+///
+/// Each action is reverted by [`Board::unmake`] after `f` returns,
+/// so that `board` is restored when this function returns.
+/// The iteration stops as soon as `f` returns [`ControlFlow::Break`].
+pub(crate) fn try_for_each_next_board<B, F>(
+    board: &mut Board,
+    player: Color,
+    rule: GameRule,
+    f: F,
+) -> ControlFlow<B>
+where
+    F: FnMut(Action, &mut Board, NextBoardStatus) -> ControlFlow<B>,
+{
+    let actions = board.legal_actions(player, true, true, *rule.is_remove_accepted());
+    try_for_each_action(board, player, rule, actions, f)
+}
 
-        use SurroundedStatus::*;
-        let sur_status = next_board.surrounded_status();
-        let is_both = matches!(sur_status, Both);
+/// Calls `f` in the same way as [`try_for_each_next_board`]
+/// but skips actions leading to boards equivalent to those already visited
+/// if `board` is symmetric (see [`Board::legal_actions_unique`]).
+///
+/// It is suitable for evaluating boards, where equivalent boards have the same value,
+/// but not for collecting actions.
+pub(crate) fn try_for_each_unique_next_board<B, F>(
+    board: &mut Board,
+    player: Color,
+    rule: GameRule,
+    f: F,
+) -> ControlFlow<B>
+where
+    F: FnMut(Action, &mut Board, NextBoardStatus) -> ControlFlow<B>,
+{
+    let is_remove_accepted = *rule.is_remove_accepted();
+    // Boards without symmetry rarely have equivalent next boards,
+    // which are not worth the cost of detection
+    let actions = if board.is_symmetric() {
+        board.legal_actions_unique(player, true, true, is_remove_accepted)
+    } else {
+        board.legal_actions(player, true, true, is_remove_accepted)
+    };
+    try_for_each_action(board, player, rule, actions, f)
+}
 
-        use NextBoardStatus::*;
-        let next_board_status = if (self.wins_if_both && is_both)
-            || matches!(sur_status, OneSide(p) if p != self.current_player)
-        {
-            Win
-        } else if (!self.wins_if_both && is_both)
-            || matches!(sur_status, OneSide(p) if p == self.current_player)
-        {
-            Lose
-        } else {
-            Unknown
-        };
-        Some((action, next_board, next_board_status))
-    }
+fn try_for_each_action<B, F>(
+    board: &mut Board,
+    player: Color,
+    rule: GameRule,
+    actions: ActionsFwd,
+    mut f: F,
+) -> ControlFlow<B>
+where
+    F: FnMut(Action, &mut Board, NextBoardStatus) -> ControlFlow<B>,
+{
+    for action in actions {
+        let token = board.make(action);
+        let status = NextBoardStatus::of(board, player, &rule);
+        let flow = f(action, board, status);
+        board.unmake(token);
+        flow?;
+    }
+    ControlFlow::Continue(())
+}
+
+/// Calls `f` for every legal action of `player` in the same way as [`try_for_each_next_board`].
+pub(crate) fn for_each_next_board<F>(board: &mut Board, player: Color, rule: GameRule, mut f: F)
+where
+    F: FnMut(Action, &mut Board, NextBoardStatus),
+{
+    let _ = try_for_each_next_board(board, player, rule, |action, next_board, status| {
+        f(action, next_board, status);
+        ControlFlow::<()>::Continue(())
+    });
 }
 
 // ****************************************************************************
@@ -581,13 +808,14 @@ impl Iterator for NextBoardIter {
 /// A tree structure that contains [`Board`] and [`BoardValue`] on its nodes,
 /// and [`Action`]s on its edges.
 ///
-/// It is returned by [`create_checkmate_tree`].
+/// It is returned by [`create_checkmate_tree`]
+/// and [`create_forcing_tree`](`super::create_forcing_tree`).
 #[derive(Clone)]
 pub struct BoardValueTree {
     board_raw: u64,
     player: Color,
-    value: BoardValue,
-    actions2children: HashMap<Action, BoardValueTree>,
+    pub(crate) value: BoardValue,
+    pub(crate) actions2children: HashMap<Action, BoardValueTree>,
 }
 
 impl std::fmt::Debug for BoardValueTree {
@@ -601,6 +829,43 @@ impl std::fmt::Debug for BoardValueTree {
     }
 }
 
+/// Serializes the tree as a nested structure
+/// with fields `board`, `player`, `value` and `children`,
+/// where `children` is a sequence of pairs of an [`Action`] and a subtree.
+#[cfg(feature = "serde")]
+impl serde::Serialize for BoardValueTree {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let children: Vec<_> = self.actions2children.iter().collect();
+        let mut state = serializer.serialize_struct("BoardValueTree", 4)?;
+        state.serialize_field("board", &self.board())?;
+        state.serialize_field("player", &self.player)?;
+        state.serialize_field("value", &self.value)?;
+        state.serialize_field("children", &children)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for BoardValueTree {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "BoardValueTree")]
+        struct Fields {
+            board: Board,
+            player: Color,
+            value: BoardValue,
+            children: Vec<(Action, BoardValueTree)>,
+        }
+
+        let fields = Fields::deserialize(deserializer)?;
+        let mut tree = BoardValueTree::new(fields.board, fields.player);
+        tree.value = fields.value;
+        tree.actions2children = fields.children.into_iter().collect();
+        Ok(tree)
+    }
+}
+
 impl std::fmt::Display for BoardValueTree {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self.display().fmt(f)
@@ -608,7 +873,7 @@ impl std::fmt::Display for BoardValueTree {
 }
 
 impl BoardValueTree {
-    fn new(board: Board, player: Color) -> Self {
+    pub(crate) fn new(board: Board, player: Color) -> Self {
         Self {
             board_raw: board.to_u64(),
             player,
@@ -913,17 +1178,14 @@ impl<'a> TreeDisplay<'a> {
 // ****************************************************************************
 //  Helper Items
 // ****************************************************************************
-fn validate_args(board: Board, value: BoardValue, rule: GameRule) -> Result<(), error::Error> {
+pub(crate) fn validate_args(board: Board, value: BoardValue) -> Result<(), error::Error> {
     use error::ArgsValidationErrorKind::*;
     if board.surrounded_status() != SurroundedStatus::None {
-        return Err(FinishedGameBoard(board).into());
+        return Err(FinishedGameBoard(Box::new(board)).into());
     }
-    if value.is_finished() || value.is_unknown() {
+    if !(value.is_win() || value.is_lose()) {
         return Err(UnsupportedValue(value).into());
     }
-    if !matches!(rule.suicide_atk_judge(), Judge::NextWins | Judge::LastWins) {
-        return Err(DrawJudge.into());
-    }
     Ok(())
 }
 
@@ -931,11 +1193,36 @@ fn validate_args(board: Board, value: BoardValue, rule: GameRule) -> Result<(),
 ///
 /// An interval is returned by [`evaluate_board`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Interval {
     left: BoardValue,
     right: BoardValue,
 }
 
+/// Deserializes the interval in the same encoding as [`Serialize`](`serde::Serialize`),
+/// checking that the left end is not greater than the right end.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Interval {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "Interval")]
+        struct Fields {
+            left: BoardValue,
+            right: BoardValue,
+        }
+
+        let Fields { left, right } = Fields::deserialize(deserializer)?;
+        if left > right {
+            return Err(D::Error::custom(format!(
+                "left end {left} is greater than right end {right}"
+            )));
+        }
+        Ok(Interval::new(left, right))
+    }
+}
+
 impl std::fmt::Display for Interval {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "[{0}, {1}]", self.left, self.right)
@@ -963,6 +1250,18 @@ impl Interval {
         self.left <= *item && *item <= self.right
     }
 
+    /// Returns the interval of values not determined by searching `search_depth` turns.
+    pub(crate) fn beyond(search_depth: usize) -> Self {
+        let (left_num, right_num) = if search_depth % 2 == 0 {
+            (search_depth + 2, search_depth + 1)
+        } else {
+            (search_depth + 1, search_depth + 2)
+        };
+        let left = BoardValue::from(Some(left_num));
+        let right = BoardValue::from(Some(right_num));
+        Interval::new(left, right)
+    }
+
     /// Returns one value in the interval if left and right values are the same.
     ///
     /// # Examples
@@ -1007,55 +1306,76 @@ impl Interval {
 /// Returns `Err` when the argument is invalid. Specifically,
 /// the following cases are invalid:
 /// - `board` is already finished (at least one boss is surrounded)
-/// - `value` is `Unknown`, `Win(even number)` or `Lose(odd or zero)`
-/// - `rule.suicide_atk_judge` is `Judge::Draw`
+/// - `value` is `Unknown`, `Draw(n)`, `Win(even number)` or `Lose(odd or zero)`
 pub fn create_checkmate_tree(
-    board: Board,
+    mut board: Board,
     player: Color,
     max_depth: usize,
     rule: GameRule,
 ) -> Result<BoardValueTree, error::Error> {
-    validate_args(board, BoardValue::MAX, rule)?;
-    Ok(create_checkmate_tree_unchecked(
-        board, player, max_depth, rule,
-    ))
+    validate_args(board, BoardValue::MAX)?;
+    let limit = SearchLimit::new();
+    let mut stats = SearchStats::default();
+    Ok(
+        create_checkmate_tree_unchecked(&mut board, player, max_depth, rule, &limit, &mut stats)
+            .unwrap(),
+    )
 }
 
-fn create_checkmate_tree_unchecked(
-    board: Board,
+/// Returns `None` if the creation was interrupted by `limit`.
+pub(crate) fn create_checkmate_tree_unchecked(
+    board: &mut Board,
     player: Color,
     max_depth: usize,
     rule: GameRule,
-) -> BoardValueTree {
+    limit: &SearchLimit,
+    stats: &mut SearchStats,
+) -> Option<BoardValueTree> {
+    if limit.is_reached() {
+        return None;
+    }
     if max_depth == 0 {
-        return BoardValueTree::new(board, player);
+        return Some(BoardValueTree::new(*board, player));
     }
 
-    let mut tree = BoardValueTree::new(board, player);
+    let mut tree = BoardValueTree::new(*board, player);
     tree.value = BoardValue::MIN;
 
-    for (action, next_board, status) in NextBoardIter::new(board, player, rule) {
+    stats.enter();
+    let mut is_interrupted = false;
+    let flow = try_for_each_next_board(board, player, rule, |action, next_board, status| {
         use NextBoardStatus::*;
         match status {
-            Win => {
-                tree.value = BoardValue::win(1).unwrap();
-                tree.actions2children.clear();
-                NextBoardIter::new(board, player, rule)
-                    .filter(|(_, _, s)| matches!(s, Win))
-                    .for_each(|(action_, next_board_, _)| {
-                        let mut child = BoardValueTree::new(next_board_, player);
-                        child.value = BoardValue::finished();
-                        tree.actions2children.insert(action_, child);
-                    });
-                return tree;
+            Win => return ControlFlow::Break(()),
+            Lose => (),
+            Draw => {
+                let mut child = BoardValueTree::new(*next_board, !player);
+                child.value = BoardValue::finished();
+                let draw = BoardValue::draw(1).unwrap();
+                if draw < tree.value {
+                    return ControlFlow::Continue(());
+                }
+                if draw > tree.value {
+                    tree.value = draw;
+                    tree.actions2children.clear();
+                }
+                tree.actions2children.insert(action, child);
             }
-            Lose => continue,
             Unknown => {
-                let child =
-                    create_checkmate_tree_unchecked(next_board, !player, max_depth - 1, rule);
+                let Some(child) = create_checkmate_tree_unchecked(
+                    next_board,
+                    !player,
+                    max_depth - 1,
+                    rule,
+                    limit,
+                    stats,
+                ) else {
+                    is_interrupted = true;
+                    return ControlFlow::Break(());
+                };
                 let child_value_increment = child.value.increment();
                 if child_value_increment < tree.value {
-                    continue;
+                    return ControlFlow::Continue(());
                 }
                 if child_value_increment > tree.value {
                     tree.value = child_value_increment;
@@ -1064,8 +1384,26 @@ fn create_checkmate_tree_unchecked(
                 tree.actions2children.insert(action, child);
             }
         }
+        ControlFlow::Continue(())
+    });
+    stats.leave();
+
+    if is_interrupted {
+        return None;
+    }
+    if flow.is_break() {
+        stats.count_cutoff();
+        tree.value = BoardValue::win(1).unwrap();
+        tree.actions2children.clear();
+        for_each_next_board(board, player, rule, |action, next_board, status| {
+            if status == NextBoardStatus::Win {
+                let mut child = BoardValueTree::new(*next_board, player);
+                child.value = BoardValue::finished();
+                tree.actions2children.insert(action, child);
+            }
+        });
     }
-    tree
+    Some(tree)
 }
 
 /// Creates an [`BoardValueTree`] that describes routes to ends of the game.
@@ -1080,20 +1418,19 @@ fn create_checkmate_tree_unchecked(
 /// Returns `Err` when the argument is invalid. Specifically,
 /// the following cases are invalid:
 /// - `board` is already finished (at least one boss is surrounded)
-/// - `value` is `Unknown`, `Win(even number)` or `Lose(odd or zero)`
-/// - `rule.suicide_atk_judge` is `Judge::Draw`
+/// - `value` is `Unknown`, `Draw(n)`, `Win(even number)` or `Lose(odd or zero)`
 /// Furthermore, it returns `Err` when the value of `board` differs from `value`.
 /// In such a case, returned value contains a variant of [`std::cmp::Ordering`],
 /// which means that the value of board is greater (`Greater`) or less (`Less`)
 /// than the specified `value`.
 pub fn create_checkmate_tree_with_value(
-    board: Board,
+    mut board: Board,
     value: BoardValue,
     player: Color,
     rule: GameRule,
 ) -> Result<BoardValueTree, error::Error> {
-    validate_args(board, value, rule)?;
-    let (tree, cmp) = create_checkmate_tree_with_value_unchecked(board, value, player, rule);
+    validate_args(board, value)?;
+    let (tree, cmp) = create_checkmate_tree_with_value_unchecked(&mut board, value, player, rule);
     if cmp == Ordering::Equal {
         Ok(tree)
     } else {
@@ -1102,45 +1439,44 @@ pub fn create_checkmate_tree_with_value(
 }
 
 fn create_checkmate_tree_with_value_unchecked(
-    board: Board,
+    board: &mut Board,
     value: BoardValue,
     player: Color,
     rule: GameRule,
 ) -> (BoardValueTree, Ordering) {
     use Ordering::*;
-    let mut tree = BoardValueTree::new(board, player);
+    let mut tree = BoardValueTree::new(*board, player);
     tree.value = value;
     let mut cmp = Less;
-    for (action, next_board, status) in NextBoardIter::new(board, player, rule) {
+    // Breaks if the value of `board` turns out to be greater than `value`
+    let flow = try_for_each_next_board(board, player, rule, |action, next_board, status| {
         use NextBoardStatus::*;
         match status {
             Win => {
-                if value == BoardValue::MAX {
-                    cmp = Equal;
-                    let mut child = BoardValueTree::new(next_board, !player);
-                    child.value = BoardValue::finished();
-                    tree.actions2children.insert(action, child);
-                    continue;
-                } else {
-                    tree.actions2children.clear();
-                    cmp = Greater;
-                    tree.value = BoardValue::unknown();
-                    return (tree, cmp);
+                if value != BoardValue::MAX {
+                    return ControlFlow::Break(());
+                }
+                cmp = Equal;
+                let mut child = BoardValueTree::new(*next_board, !player);
+                child.value = BoardValue::finished();
+                tree.actions2children.insert(action, child);
+            }
+            Lose => (),
+            Draw => {
+                if value.is_lose() {
+                    return ControlFlow::Break(());
                 }
             }
-            Lose => continue,
             Unknown => {
                 if value == BoardValue::MAX {
-                    continue;
+                    return ControlFlow::Continue(());
                 }
                 let next_value = value.try_decrement().unwrap();
                 let (child, next_cmp) = create_checkmate_tree_with_value_unchecked(
                     next_board, next_value, !player, rule,
                 );
                 if next_cmp == Less {
-                    tree.value = BoardValue::unknown();
-                    tree.actions2children.clear();
-                    return (tree, Greater);
+                    return ControlFlow::Break(());
                 }
                 if next_cmp == Equal {
                     tree.actions2children.insert(action, child);
@@ -1148,6 +1484,10 @@ fn create_checkmate_tree_with_value_unchecked(
                 cmp = cmp.max(next_cmp.reverse());
             }
         }
+        ControlFlow::Continue(())
+    });
+    if flow.is_break() {
+        cmp = Greater;
     }
     if cmp != Equal {
         tree.value = BoardValue::unknown();
@@ -1167,51 +1507,37 @@ fn create_checkmate_tree_with_value_unchecked(
 /// Returns `Err` only when the argument is invalid. Specifically,
 /// the following cases are invalid:
 /// - `board` is already finished (at least one boss is surrounded)
-/// - `value` is `Unknown`, `Win(even number)` or `Lose(odd or zero)`
-/// - `rule.suicide_atk_judge` is `Judge::Draw`
+/// - `value` is `Unknown`, `Draw(n)`, `Win(even number)` or `Lose(odd or zero)`
 pub fn compare_board_value(
-    board: Board,
+    mut board: Board,
     value: BoardValue,
     player: Color,
     rule: GameRule,
 ) -> Result<Ordering, error::Error> {
-    validate_args(board, value, rule)?;
-    Ok(compare_board_value_unchecked(board, value, player, rule))
+    validate_args(board, value)?;
+    Ok(Searcher::new(rule).compare(&mut board, value, player))
 }
 
-fn compare_board_value_unchecked(
-    board: Board,
+/// Compares the value of specified [`Board`] to a given [`BoardValue`]
+/// with the help of [`TranspositionTable`].
+///
+/// This function returns the same result as [`compare_board_value`].
+/// Results of comparisons during the search are read from and written to `table`,
+/// which makes repeated searches much faster.
+///
+/// # Errors
+/// Returns `Err` in the same cases as [`compare_board_value`].
+pub fn compare_board_value_with_table(
+    mut board: Board,
     value: BoardValue,
     player: Color,
     rule: GameRule,
-) -> Ordering {
-    use Ordering::*;
-    let mut cmp = Less;
-    for (_, next_board, status) in NextBoardIter::new(board, player, rule) {
-        use NextBoardStatus::*;
-        match status {
-            Win => {
-                if value == BoardValue::MAX {
-                    return Equal;
-                } else {
-                    return Greater;
-                }
-            }
-            Lose => continue,
-            Unknown => {
-                if value == BoardValue::MAX {
-                    continue;
-                }
-                let next_val = value.try_decrement().unwrap();
-                let next_cmp = compare_board_value_unchecked(next_board, next_val, !player, rule);
-                if next_cmp == Less {
-                    return Greater;
-                }
-                cmp = cmp.max(next_cmp.reverse());
-            }
-        }
-    }
-    cmp
+    table: &mut TranspositionTable,
+) -> Result<Ordering, error::Error> {
+    validate_args(board, value)?;
+    Ok(Searcher::new(rule)
+        .with_table(table)
+        .compare(&mut board, value, player))
 }
 
 /// Calculates a possible range of [`BoardValue`] of specified [`Board`].
@@ -1220,127 +1546,503 @@ fn compare_board_value_unchecked(
 /// The result is returned in [`Interval`], which is a closed interval
 /// between two [`BoardValue`]s. It means that the value of the board is in the interval.
 ///
+/// Draws are never determined by this function.
+/// Under [`Judge::Draw`], if neither a win nor a loss is found within `search_depth` turns,
+/// the interval contains every `Draw(n)` as well as `Unknown`,
+/// even if the player has nothing better than drawing immediately.
+/// Use [`create_checkmate_tree`] or [`find_best_actions`] to order draws among them.
+///
 /// # Errors
 /// Returns `Err` only when the argument is invalid. Specifically,
 /// the following cases are invalid:
 /// - `board` is already finished (at least one boss is surrounded)
-/// - `rule.suicide_atk_judge` is `Judge::Draw`
 pub fn evaluate_board(
-    board: Board,
+    mut board: Board,
     player: Color,
     search_depth: usize,
     rule: GameRule,
 ) -> Result<Interval, error::Error> {
-    validate_args(board, BoardValue::MAX, rule)?;
-    Ok(evaluate_board_unchecked(board, player, search_depth, rule))
+    validate_args(board, BoardValue::MAX)?;
+    Ok(Searcher::new(rule).evaluate(&mut board, player, search_depth))
 }
 
-fn evaluate_board_unchecked(
-    board: Board,
+/// Calculates a possible range of [`BoardValue`] of specified [`Board`]
+/// with the help of [`TranspositionTable`].
+///
+/// This function returns the same result as [`evaluate_board`].
+/// Results of comparisons during the search are read from and written to `table`.
+///
+/// # Errors
+/// Returns `Err` in the same cases as [`evaluate_board`].
+pub fn evaluate_board_with_table(
+    mut board: Board,
     player: Color,
     search_depth: usize,
     rule: GameRule,
-) -> Interval {
-    for depth in 1..=search_depth {
-        let value = BoardValue::from(Some(depth));
-        if matches!(
-            compare_board_value_unchecked(board, value, player, rule),
-            Ordering::Equal
-        ) {
-            return Interval::new(value, value);
-        }
-    }
-    let (left_num, right_num) = if search_depth % 2 == 0 {
-        (search_depth + 2, search_depth + 1)
-    } else {
-        (search_depth + 1, search_depth + 2)
-    };
-    let left = BoardValue::from(Some(left_num));
-    let right = BoardValue::from(Some(right_num));
-    Interval::new(left, right)
+    table: &mut TranspositionTable,
+) -> Result<Interval, error::Error> {
+    validate_args(board, BoardValue::MAX)?;
+    Ok(Searcher::new(rule)
+        .with_table(table)
+        .evaluate(&mut board, player, search_depth))
 }
 
 /// Collects the best [`Action`]s by [`BoardValue`].
 ///
+/// The actions are ordered by the values of the next boards as in [`BoardValue`].
+/// Especially, if the value of `board` is not determined under [`Judge::Draw`],
+/// an action to draw immediately (`Draw(1)`) is preferred to an unknown result,
+/// which is preferred to a draw made by the opponent (`Draw(2)`).
+/// The result coincides with the actions at the root of [`create_checkmate_tree`].
+///
 /// # Errors
 /// Returns `Err` only when the argument is invalid. Specifically,
 /// the following cases are invalid:
 /// - `board` is already finished (at least one boss is surrounded)
-/// - `rule.suicide_atk_judge` is `Judge::Draw`
 pub fn find_best_actions(
-    board: Board,
+    mut board: Board,
     player: Color,
     search_depth: usize,
     rule: GameRule,
 ) -> Result<Vec<Action>, error::Error> {
-    validate_args(board, BoardValue::MAX, rule)?;
-    Ok(find_best_actions_unchecked(
-        board,
-        player,
-        search_depth,
-        rule,
-    ))
+    validate_args(board, BoardValue::MAX)?;
+    Ok(Searcher::new(rule).find_best_actions(&mut board, player, search_depth))
 }
 
-fn find_best_actions_unchecked(
-    board: Board,
+/// Collects the best [`Action`]s by [`BoardValue`]
+/// with the help of [`TranspositionTable`].
+///
+/// This function returns the same result as [`find_best_actions`].
+/// Results of comparisons during the search are read from and written to `table`.
+///
+/// # Errors
+/// Returns `Err` in the same cases as [`find_best_actions`].
+pub fn find_best_actions_with_table(
+    mut board: Board,
     player: Color,
     search_depth: usize,
     rule: GameRule,
-) -> Vec<Action> {
-    if search_depth == 0 {
-        return board
-            .legal_actions(player, true, true, *rule.is_remove_accepted())
-            .into_iter()
-            .collect();
+    table: &mut TranspositionTable,
+) -> Result<Vec<Action>, error::Error> {
+    validate_args(board, BoardValue::MAX)?;
+    Ok(Searcher::new(rule)
+        .with_table(table)
+        .find_best_actions(&mut board, player, search_depth))
+}
+
+// ****************************************************************************
+//  Searcher
+// ****************************************************************************
+/// The least number n of [`BoardValue`] (`Win(n)` or `Lose(n)`)
+/// whose comparisons are shared among threads via a shared table.
+/// Comparisons with smaller values are cheaper than locking the table.
+const SHARED_TABLE_MIN_NUM: usize = 4;
+
+/// The number of checks of stopping between readings of the clock (a power of two).
+/// Reading the clock at every node would be too expensive.
+const CLOCK_CHECK_INTERVAL: usize = 256;
+
+/// A context shared in a search by functions for analysis
+pub(crate) struct Searcher<'a> {
+    rule: GameRule,
+    table: Option<&'a mut TranspositionTable>,
+    shared_table: Option<&'a Mutex<TranspositionTable>>,
+    stop_flag: Option<&'a AtomicBool>,
+    limit: Option<&'a SearchLimit>,
+    tablebase: Option<&'a Tablebase>,
+    /// Set once the search is stopped so that the rest of the search is skipped
+    is_stopped: Cell<bool>,
+    num_checks: Cell<usize>,
+    stats: SearchStats,
+}
+
+impl<'a> Searcher<'a> {
+    pub(crate) fn new(rule: GameRule) -> Self {
+        Self {
+            rule,
+            table: None,
+            shared_table: None,
+            stop_flag: None,
+            limit: None,
+            tablebase: None,
+            is_stopped: Cell::new(false),
+            num_checks: Cell::new(0),
+            stats: SearchStats::default(),
+        }
     }
 
-    let value_interval = evaluate_board_unchecked(board, player, search_depth, rule);
-    let value = value_interval.single().unwrap_or(BoardValue::unknown());
+    pub(crate) fn with_table(self, table: &'a mut TranspositionTable) -> Self {
+        table.adapt_to(&self.rule);
+        Self {
+            table: Some(table),
+            ..self
+        }
+    }
 
-    let mut actions = Vec::new();
-    for (action, next_board, status) in NextBoardIter::new(board, player, rule) {
-        use NextBoardStatus::*;
-        match status {
-            Win => {
-                if value != BoardValue::MAX {
-                    unreachable!()
+    /// Sets a table shared with other threads.
+    ///
+    /// The table must have been adapted to the rule in advance.
+    pub(crate) fn with_shared_table(self, shared_table: &'a Mutex<TranspositionTable>) -> Self {
+        Self {
+            shared_table: Some(shared_table),
+            ..self
+        }
+    }
+
+    /// Sets a flag to stop the search.
+    ///
+    /// Once the flag is set, results of the search are meaningless
+    /// and they are not stored in tables.
+    pub(crate) fn with_stop_flag(self, stop_flag: &'a AtomicBool) -> Self {
+        Self {
+            stop_flag: Some(stop_flag),
+            ..self
+        }
+    }
+
+    /// Sets a limit to stop the search.
+    ///
+    /// Once the limit is reached, results of the search are meaningless
+    /// and they are not stored in tables.
+    pub(crate) fn with_limit(self, limit: &'a SearchLimit) -> Self {
+        Self {
+            limit: Some(limit),
+            ..self
+        }
+    }
+
+    /// Sets a tablebase consulted during the search.
+    ///
+    /// The tablebase is ignored if it was generated under a different rule.
+    pub(crate) fn with_tablebase(self, tablebase: &'a Tablebase) -> Self {
+        if !tablebase.is_compatible_with(&self.rule) {
+            return self;
+        }
+        Self {
+            tablebase: Some(tablebase),
+            ..self
+        }
+    }
+
+    /// Returns statistics of the searches so far.
+    ///
+    /// The elapsed time is not measured by the searcher.
+    pub(crate) fn stats(&self) -> &SearchStats {
+        &self.stats
+    }
+
+    pub(crate) fn is_stopped(&self) -> bool {
+        if self.is_stopped.get() {
+            return true;
+        }
+        let is_stopped = self
+            .stop_flag
+            .is_some_and(|flag| flag.load(AtomicOrdering::Relaxed))
+            || self.limit.is_some_and(|limit| {
+                let num_checks = self.num_checks.get();
+                self.num_checks.set(num_checks + 1);
+                limit.is_cancelled()
+                    || (num_checks & (CLOCK_CHECK_INTERVAL - 1) == 0 && limit.is_expired())
+            });
+        self.is_stopped.set(is_stopped);
+        is_stopped
+    }
+
+    pub(crate) fn compare(
+        &mut self,
+        board: &mut Board,
+        value: BoardValue,
+        player: Color,
+    ) -> Ordering {
+        if self.is_stopped() {
+            return Ordering::Less;
+        }
+
+        if let Some(tablebase) = self.tablebase {
+            if let Some(cmp) = self.compare_by_tablebase(tablebase, board, value, player) {
+                self.stats.count_table_hit();
+                return cmp;
+            }
+        }
+
+        // Comparisons with BoardValue::MAX are too cheap to be memorized
+        let uses_table = value != BoardValue::MAX && self.table.is_some();
+        let uses_shared_table =
+            value.try_unwrap().unwrap_or(0) >= SHARED_TABLE_MIN_NUM && self.shared_table.is_some();
+        let key = if uses_table || uses_shared_table {
+            Some(TranspositionTable::key(board, player))
+        } else {
+            None
+        };
+
+        if let Some(key) = key {
+            let mut cmp = None;
+            if uses_table {
+                cmp = self
+                    .table
+                    .as_ref()
+                    .and_then(|table| table.probe(key, value));
+            }
+            if cmp.is_none() && uses_shared_table {
+                cmp = self
+                    .shared_table
+                    .and_then(|table| table.lock().unwrap().probe(key, value));
+            }
+            if let Some(cmp) = cmp {
+                self.stats.count_table_hit();
+                return cmp;
+            }
+        }
+
+        let cmp = self.compare_without_table(board, value, player);
+        if self.is_stopped() {
+            return cmp;
+        }
+        if let Some(key) = key {
+            if uses_table {
+                if let Some(table) = self.table.as_mut() {
+                    table.store(key, value, cmp);
+                }
+            }
+            if uses_shared_table {
+                if let Some(table) = self.shared_table {
+                    table.lock().unwrap().store(key, value, cmp);
                 }
-                actions.push(action);
-                continue;
             }
-            Lose => continue,
-            Unknown => (),
         }
+        cmp
+    }
 
+    fn compare_without_table(
+        &mut self,
+        board: &mut Board,
+        value: BoardValue,
+        player: Color,
+    ) -> Ordering {
+        use Ordering::*;
+        let rule = self.rule;
         if value == BoardValue::MAX {
-            continue;
+            // Only immediate wins matter, which are found without performing every action
+            let includes_both = matches!(rule.suicide_atk_judge(), Judge::LastWins);
+            self.stats.enter();
+            let is_empty = board.surrounding_actions(player, includes_both).is_empty();
+            self.stats.leave();
+            if is_empty {
+                return Less;
+            }
+            self.stats.count_cutoff();
+            return Equal;
+        }
+        let mut cmp = Less;
+        self.stats.enter();
+        let flow = try_for_each_unique_next_board(board, player, rule, |_, next_board, status| {
+            use NextBoardStatus::*;
+            match status {
+                Win => {
+                    if value == BoardValue::MAX {
+                        return ControlFlow::Break(Equal);
+                    } else {
+                        return ControlFlow::Break(Greater);
+                    }
+                }
+                Lose => (),
+                Draw => {
+                    if value.is_lose() {
+                        return ControlFlow::Break(Greater);
+                    }
+                }
+                Unknown => {
+                    if value == BoardValue::MAX {
+                        return ControlFlow::Continue(());
+                    }
+                    let next_val = value.try_decrement().unwrap();
+                    let next_cmp = self.compare(next_board, next_val, !player);
+                    if next_cmp == Less {
+                        return ControlFlow::Break(Greater);
+                    }
+                    cmp = cmp.max(next_cmp.reverse());
+                }
+            }
+            ControlFlow::Continue(())
+        });
+        self.stats.leave();
+        match flow {
+            ControlFlow::Break(cmp) => {
+                self.stats.count_cutoff();
+                cmp
+            }
+            ControlFlow::Continue(()) => cmp,
         }
+    }
 
-        let next_value = value.try_decrement().unwrap();
-        if next_value.is_unknown() {
-            if !matches!(
-                compare_board_value_unchecked(
-                    next_board,
-                    value_interval.left().try_decrement().unwrap(),
-                    !player,
-                    rule
-                ),
-                Ordering::Greater
-            ) {
-                actions.push(action);
+    pub(crate) fn evaluate(
+        &mut self,
+        board: &mut Board,
+        player: Color,
+        search_depth: usize,
+    ) -> Interval {
+        for depth in 1..=search_depth {
+            let value = BoardValue::from(Some(depth));
+            if matches!(self.compare(board, value, player), Ordering::Equal) {
+                return Interval::new(value, value);
             }
-        } else {
-            // next_value is Win or Lose
-            if matches!(
-                compare_board_value_unchecked(next_board, next_value, !player, rule),
-                Ordering::Equal
-            ) {
+        }
+        let interval = Interval::beyond(search_depth);
+        // Values in the tablebase may be beyond `search_depth`
+        match self
+            .tablebase
+            .and_then(|tablebase| tablebase.get(board, player))
+        {
+            Some(bound) if bound.is_win() => Interval::new(bound, *interval.right()),
+            Some(bound) => Interval::new(*interval.left(), bound),
+            None => interval,
+        }
+    }
+
+    /// Collects the best actions in the same way as [`create_checkmate_tree`],
+    /// where draws are ordered among undetermined values as `Draw(1) > Unknown > Draw(2)`.
+    fn find_best_actions_with_draws(
+        &mut self,
+        board: &mut Board,
+        player: Color,
+        search_depth: usize,
+    ) -> Vec<Action> {
+        let mut values = HashMap::new();
+        let mut best = BoardValue::MIN;
+        let mut actions = Vec::new();
+        let rule = self.rule;
+        for_each_next_board(board, player, rule, |action, next_board, status| {
+            use NextBoardStatus::*;
+            let value = match status {
+                Win => BoardValue::MAX,
+                Lose => return,
+                Draw => BoardValue::draw(1).unwrap(),
+                Unknown => self
+                    .value_with_draws(next_board, !player, search_depth - 1, &mut values)
+                    .increment(),
+            };
+            if value > best {
+                best = value;
+                actions.clear();
+            }
+            if value == best {
                 actions.push(action);
             }
+        });
+        actions
+    }
+
+    /// Calculates the value of `board` that coincides with
+    /// the value of the tree created by [`create_checkmate_tree`] with `depth`.
+    ///
+    /// `values` memorizes the values of equivalent boards
+    /// indexed by their hashes and depths,
+    /// together with [`Board::to_invariant_u64`] to detect hash collisions.
+    pub(crate) fn value_with_draws(
+        &mut self,
+        board: &mut Board,
+        player: Color,
+        depth: usize,
+        values: &mut HashMap<(u64, usize), (u64, BoardValue)>,
+    ) -> BoardValue {
+        if depth == 0 || self.is_stopped() {
+            return BoardValue::unknown();
+        }
+        let key = (board.invariant_zobrist_hash(player), depth);
+        let exact = board.to_invariant_u64(player);
+        if let Some(&(_, value)) = values.get(&key).filter(|(e, _)| *e == exact) {
+            return value;
+        }
+
+        let mut value = BoardValue::MIN;
+        let rule = self.rule;
+        self.stats.enter();
+        let flow = try_for_each_unique_next_board(board, player, rule, |_, next_board, status| {
+            use NextBoardStatus::*;
+            let next_value = match status {
+                Win => return ControlFlow::Break(()),
+                Lose => return ControlFlow::Continue(()),
+                Draw => BoardValue::draw(1).unwrap(),
+                Unknown => self
+                    .value_with_draws(next_board, !player, depth - 1, values)
+                    .increment(),
+            };
+            if next_value > value {
+                value = next_value;
+            }
+            ControlFlow::Continue(())
+        });
+        self.stats.leave();
+        if flow.is_break() {
+            value = BoardValue::MAX;
         }
+        values.insert(key, (exact, value));
+        value
+    }
+
+    pub(crate) fn find_best_actions(
+        &mut self,
+        board: &mut Board,
+        player: Color,
+        search_depth: usize,
+    ) -> Vec<Action> {
+        if search_depth == 0 {
+            return board
+                .legal_actions(player, true, true, *self.rule.is_remove_accepted())
+                .into_iter()
+                .collect();
+        }
+
+        let value_interval = self.evaluate(board, player, search_depth);
+        let value = value_interval.single().unwrap_or(BoardValue::unknown());
+        let rule = self.rule;
+        if value.is_unknown() && matches!(rule.suicide_atk_judge(), Judge::Draw) {
+            return self.find_best_actions_with_draws(board, player, search_depth);
+        }
+
+        let mut actions = Vec::new();
+        for_each_next_board(board, player, rule, |action, next_board, status| {
+            use NextBoardStatus::*;
+            match status {
+                Win => {
+                    if value != BoardValue::MAX {
+                        unreachable!()
+                    }
+                    actions.push(action);
+                    return;
+                }
+                Lose | Draw => return,
+                Unknown => (),
+            }
+
+            if value == BoardValue::MAX {
+                return;
+            }
+
+            let next_value = value.try_decrement().unwrap();
+            if next_value.is_unknown() {
+                if !matches!(
+                    self.compare(
+                        next_board,
+                        value_interval.left().try_decrement().unwrap(),
+                        !player,
+                    ),
+                    Ordering::Greater
+                ) {
+                    actions.push(action);
+                }
+            } else {
+                // next_value is Win or Lose
+                if matches!(
+                    self.compare(next_board, next_value, !player),
+                    Ordering::Equal
+                ) {
+                    actions.push(action);
+                }
+            }
+        });
+        actions
     }
-    actions
 }
 
 // ************************************************************
@@ -1443,4 +2145,162 @@ mod tests {
             assert!(tree.is_good_for_puzzle(num - 2));
         }
     }
+
+    #[test]
+    fn test_with_table() {
+        use std::str::FromStr;
+        let rule = game::GameRule::new(true).with_suicide_atk_judge(game::Judge::NextWins);
+        let board_strs = [
+            " B; a;TH y;b mM",
+            " By;H  a;A m;  Yb",
+            "bB; H;Y h;  T",
+            "hB A;maYT; Htb;M y",
+            "bB;T YA",
+            "b;B",
+        ];
+        let mut table = analysis::TranspositionTable::new();
+        for s in board_strs {
+            let board = BoardBuilder::from_str(s).unwrap().build().unwrap();
+            for player in [Color::Red, Color::Green] {
+                let evaluated = analysis::evaluate_board(board, player, 5, rule).unwrap();
+                let evaluated_with_table =
+                    analysis::evaluate_board_with_table(board, player, 5, rule, &mut table)
+                        .unwrap();
+                assert_eq!(evaluated, evaluated_with_table);
+
+                let mut best = analysis::find_best_actions(board, player, 5, rule).unwrap();
+                let mut best_with_table =
+                    analysis::find_best_actions_with_table(board, player, 5, rule, &mut table)
+                        .unwrap();
+                best.sort_by_key(|a| format!("{a:?}"));
+                best_with_table.sort_by_key(|a| format!("{a:?}"));
+                assert_eq!(best, best_with_table);
+
+                for num in 1..=5 {
+                    let value = BoardValue::from(Some(num));
+                    assert_eq!(
+                        analysis::compare_board_value(board, value, player, rule).unwrap(),
+                        analysis::compare_board_value_with_table(
+                            board, value, player, rule, &mut table
+                        )
+                        .unwrap()
+                    );
+                }
+            }
+        }
+        assert!(!table.is_empty());
+
+        // The table is cleared when it is used with another rule
+        let rule_no_remove = rule.with_is_remove_accepted(false);
+        let board = BoardBuilder::from_str("b;B").unwrap().build().unwrap();
+        analysis::evaluate_board_with_table(board, Color::Red, 1, rule_no_remove, &mut table)
+            .unwrap();
+        assert!(table.is_empty());
+    }
+
+    #[test]
+    fn test_draw_judge() {
+        use std::str::FromStr;
+        let rule = game::GameRule::new(true).with_suicide_atk_judge(game::Judge::Draw);
+        let board_strs = [
+            "    ;btBy;AaMm; H T",
+            "    ;BMba;AYty;  Th",
+            "myb ; YB ;  T ; H  ",
+            " tm ;hyb ; HB ; MT ",
+            "   b;HMBA;Taym;htY ",
+            " B; a;TH y;b mM",
+            "bB;T YA",
+        ];
+        let depth = 3;
+        for s in board_strs {
+            let board = BoardBuilder::from_str(s).unwrap().build().unwrap();
+            for player in [Color::Red, Color::Green] {
+                let tree = analysis::create_checkmate_tree(board, player, depth, rule).unwrap();
+                let value = *tree.value();
+                let evaluated = analysis::evaluate_board(board, player, depth, rule).unwrap();
+                let mut best = analysis::find_best_actions(board, player, depth, rule).unwrap();
+                let mut tree_actions: Vec<_> = tree.actions().copied().collect();
+                best.sort_by_key(|a| format!("{a:?}"));
+                tree_actions.sort_by_key(|a| format!("{a:?}"));
+                // Draws are ordered in the same way: Draw(1) > Unknown > Draw(2)
+                assert_eq!(best, tree_actions, "{s} {player:?}");
+                if value.is_win() || value.is_lose() {
+                    assert_eq!(evaluated.single(), Some(value));
+                    assert_eq!(
+                        analysis::compare_board_value(board, value, player, rule).unwrap(),
+                        std::cmp::Ordering::Equal
+                    );
+                } else {
+                    assert!(evaluated.single().is_none());
+                    assert!(evaluated.contains(&value));
+                }
+                if value == BoardValue::draw(1).unwrap() {
+                    assert!(tree.children().all(|child| child.value().is_finished()));
+                }
+            }
+        }
+
+        // Surrounding both bosses is not a win under Judge::Draw
+        let board = BoardBuilder::from_str("    ;btBy;AaMm; H T")
+            .unwrap()
+            .build()
+            .unwrap();
+        let tree = analysis::create_checkmate_tree(board, Color::Red, 3, rule).unwrap();
+        assert_eq!(*tree.value(), BoardValue::draw(1).unwrap());
+        // evaluate_board does not determine the draw
+        let evaluated = analysis::evaluate_board(board, Color::Red, 3, rule).unwrap();
+        assert_eq!(evaluated, analysis::Interval::beyond(3));
+        assert!(evaluated.contains(&BoardValue::draw(1).unwrap()));
+        assert!(evaluated.contains(&BoardValue::draw(2).unwrap()));
+        let last_wins = rule.with_suicide_atk_judge(game::Judge::LastWins);
+        let evaluated = analysis::evaluate_board(board, Color::Red, 3, last_wins).unwrap();
+        assert_eq!(evaluated.single(), Some(BoardValue::MAX));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        use analysis::{BoardValueTree, Interval};
+        use std::str::FromStr;
+
+        let values = [
+            BoardValue::win(3).unwrap(),
+            BoardValue::lose(2).unwrap(),
+            BoardValue::draw(1).unwrap(),
+            BoardValue::unknown(),
+            BoardValue::finished(),
+        ];
+        for value in values {
+            let json = serde_json::to_string(&value).unwrap();
+            assert_eq!(json, format!("\"{value}\""));
+            assert_eq!(serde_json::from_str::<BoardValue>(&json).unwrap(), value);
+        }
+        assert!(serde_json::from_str::<BoardValue>(r#""Win(2)""#).is_err());
+
+        let interval = Interval::new(BoardValue::MIN, BoardValue::MAX);
+        let json = serde_json::to_string(&interval).unwrap();
+        assert_eq!(json, r#"{"left":"Lose(2)","right":"Win(1)"}"#);
+        assert_eq!(serde_json::from_str::<Interval>(&json).unwrap(), interval);
+        let json = r#"{"left":"Win(1)","right":"Lose(2)"}"#;
+        assert!(serde_json::from_str::<Interval>(json).is_err());
+
+        fn assert_tree_eq(tree1: &BoardValueTree, tree2: &BoardValueTree) {
+            assert_eq!(tree1.board(), tree2.board());
+            assert_eq!(tree1.player(), tree2.player());
+            assert_eq!(tree1.value(), tree2.value());
+            assert_eq!(tree1.num_children(), tree2.num_children());
+            for (action, child) in tree1.actions_children() {
+                assert_tree_eq(child, tree2.child(action).unwrap());
+            }
+        }
+        let rule = game::GameRule::new(true);
+        let board = BoardBuilder::from_str("bB;T YA").unwrap().build().unwrap();
+        let value = BoardValue::win(5).unwrap();
+        let tree =
+            analysis::create_checkmate_tree_with_value(board, value, Color::Red, rule).unwrap();
+        let json = serde_json::to_string(&tree).unwrap();
+        assert_tree_eq(&serde_json::from_str(&json).unwrap(), &tree);
+        let bytes = bincode::serialize(&tree).unwrap();
+        assert_tree_eq(&bincode::deserialize(&bytes).unwrap(), &tree);
+    }
 }
@@ -0,0 +1,230 @@
+use std::fs::File;
+use std::path::Path;
+
+use crate::{collections::BoardSet, game::GameRule, Board, Color, SurroundedStatus};
+
+/// An [`Iterator`] enumerating all positions reachable from an initial board breadth-first.
+///
+/// Each item is a [`BoardSet`] containing positions first reached after the same number of plies,
+/// i.e., the n-th item (starting from zero) contains positions
+/// whose shortest distance from the initial board is n actions.
+///
+/// Positions, i.e., pairs of a board and the next player, are deduplicated
+/// by [`Board::to_invariant_u64`].
+/// Therefore a position is stored as the board normalized so that the next player is red,
+/// and positions equivalent up to symmetries and swapping colors are counted once.
+/// Finished positions (at least one boss is surrounded) are enumerated
+/// but actions from them are not.
+///
+/// Only `is_remove_accepted`, `first_player` and `initial_board` of [`GameRule`] are used,
+/// that is, the enumeration continues until no new position is found
+/// or the number of plies reaches the limit set by
+/// [`with_max_plies`](`PositionEnumerator::with_max_plies`).
+///
+/// # Examples
+/// ```rust
+/// use tokyodoves::analysis::PositionEnumerator;
+/// use tokyodoves::game::GameRule;
+///
+/// let rule = GameRule::new(true);
+/// let counts: Vec<usize> = PositionEnumerator::new(&rule)
+///     .with_max_plies(Some(3))
+///     .map(|layer| layer.len())
+///     .collect();
+/// assert_eq!(counts.len(), 4);
+/// assert_eq!(counts[0], 1); // Only the initial board
+/// ```
+#[derive(Debug, Clone)]
+pub struct PositionEnumerator {
+    is_remove_accepted: bool,
+    max_plies: Option<usize>,
+    /// The number of layers already returned
+    ply: usize,
+    /// Positions in layers already returned
+    visited: BoardSet,
+    /// Positions to be returned next
+    layer: BoardSet,
+}
+
+impl PositionEnumerator {
+    /// Creates a new enumerator starting from the initial board of `rule`.
+    pub fn new(rule: &GameRule) -> Self {
+        Self::from_board(*rule.initial_board(), *rule.first_player(), rule)
+    }
+
+    /// Creates a new enumerator starting from `board` with `player` as the next player.
+    pub fn from_board(board: Board, player: Color, rule: &GameRule) -> Self {
+        Self {
+            is_remove_accepted: *rule.is_remove_accepted(),
+            max_plies: None,
+            ply: 0,
+            visited: BoardSet::new(),
+            layer: BoardSet::from([board.normalize(player)]),
+        }
+    }
+
+    /// Returns the maximum number of plies to be enumerated.
+    pub fn max_plies(&self) -> &Option<usize> {
+        &self.max_plies
+    }
+
+    /// Returns the number of layers already returned by the iterator.
+    pub fn ply(&self) -> usize {
+        self.ply
+    }
+
+    /// Returns a set of all positions already returned by the iterator.
+    pub fn visited(&self) -> &BoardSet {
+        &self.visited
+    }
+
+    /// Update max_plies of [`PositionEnumerator`].
+    ///
+    /// The iterator returns at most `max_plies + 1` layers including the initial board.
+    /// If `max_plies` is `None`, it continues until no new position is found.
+    pub fn with_max_plies(self, max_plies: Option<usize>) -> Self {
+        Self { max_plies, ..self }
+    }
+
+    /// Enumerates all remaining layers saving each of them into the directory `dir`,
+    /// and returns the numbers of positions in the layers.
+    ///
+    /// The n-th layer is saved by [`BoardSet::save`] as a file named "ply{n}.bin"
+    /// as soon as it is found, so the layers can be inspected during the enumeration.
+    /// The directory is created if it does not exist.
+    ///
+    /// # Examples
+    /// ``` ignore
+    /// use tokyodoves::analysis::PositionEnumerator;
+    /// use tokyodoves::game::GameRule;
+    ///
+    /// let rule = GameRule::new(true);
+    /// let counts = PositionEnumerator::new(&rule)
+    ///     .with_max_plies(Some(10))
+    ///     .save_layers("/some/target/dir")?;
+    /// for (ply, count) in counts.iter().enumerate() {
+    ///     println!("{ply}: {count}");
+    /// }
+    /// ```
+    pub fn save_layers(self, dir: impl AsRef<Path>) -> std::io::Result<Vec<usize>> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        let mut counts = Vec::new();
+        let start = self.ply;
+        for (ply, layer) in (start..).zip(self) {
+            layer.save(File::create(dir.join(format!("ply{ply}.bin")))?)?;
+            counts.push(layer.len());
+        }
+        Ok(counts)
+    }
+
+    /// Collects positions after one action from positions in the current layer
+    /// which are not visited yet.
+    fn next_layer(&self) -> BoardSet {
+        let mut next_layer = BoardSet::new();
+        for mut board in self.layer.iter() {
+            if board.surrounded_status() != SurroundedStatus::None {
+                continue;
+            }
+            let actions = board.legal_actions(Color::Red, true, true, self.is_remove_accepted);
+            for action in actions {
+                let token = board.make(action);
+                let next_board = board.normalize(Color::Green);
+                board.unmake(token);
+                if !self.visited.contains(&next_board) && !self.layer.contains(&next_board) {
+                    next_layer.insert(next_board);
+                }
+            }
+        }
+        next_layer
+    }
+}
+
+impl Iterator for PositionEnumerator {
+    type Item = BoardSet;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.layer.is_empty() || self.max_plies.is_some_and(|max| self.ply > max) {
+            return None;
+        }
+        let next_layer = if self.max_plies.is_some_and(|max| self.ply == max) {
+            BoardSet::new()
+        } else {
+            self.next_layer()
+        };
+        let layer = std::mem::replace(&mut self.layer, next_layer);
+        self.visited.extend(layer.iter());
+        self.ply += 1;
+        Some(layer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_enumeration_coincides_with_naive_search() {
+        for rule in [GameRule::new(true), GameRule::new(false)] {
+            let layers: Vec<BoardSet> = PositionEnumerator::new(&rule)
+                .with_max_plies(Some(3))
+                .collect();
+            assert_eq!(layers.len(), 4);
+
+            let mut visited = HashSet::new();
+            let mut layer = vec![(Board::new(), Color::Red)];
+            for expected in layers.iter() {
+                let keys: HashSet<u64> = layer
+                    .iter()
+                    .map(|(board, player)| board.to_invariant_u64(*player))
+                    .filter(|key| !visited.contains(key))
+                    .collect();
+                assert_eq!(keys.len(), expected.len());
+                assert!(expected
+                    .iter()
+                    .all(|board| keys.contains(&board.to_invariant_u64(Color::Red))));
+                visited.extend(keys);
+
+                let mut next_layer = Vec::new();
+                for (board, player) in layer {
+                    if board.surrounded_status() != SurroundedStatus::None {
+                        continue;
+                    }
+                    for action in
+                        board.legal_actions(player, true, true, *rule.is_remove_accepted())
+                    {
+                        next_layer.push((board.perform_copied(action).unwrap(), !player));
+                    }
+                }
+                layer = next_layer;
+            }
+        }
+    }
+
+    #[test]
+    fn test_save_layers() {
+        let rule = GameRule::new(true);
+        let dir =
+            std::env::temp_dir().join(format!("tokyodoves_enumeration_{}", std::process::id()));
+        let counts = PositionEnumerator::new(&rule)
+            .with_max_plies(Some(2))
+            .save_layers(&dir)
+            .unwrap();
+        let layers: Vec<BoardSet> = (0..counts.len())
+            .map(|ply| BoardSet::new_from_file(dir.join(format!("ply{ply}.bin"))).unwrap())
+            .collect();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let expected: Vec<BoardSet> = PositionEnumerator::new(&rule)
+            .with_max_plies(Some(2))
+            .collect();
+        assert_eq!(
+            counts,
+            expected.iter().map(BoardSet::len).collect::<Vec<_>>()
+        );
+        for (layer, expected_layer) in layers.iter().zip(expected.iter()) {
+            assert!(layer.is_subset(expected_layer) && expected_layer.is_subset(layer));
+        }
+    }
+}
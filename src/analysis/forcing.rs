@@ -0,0 +1,307 @@
+use std::collections::HashMap;
+
+use crate::{
+    analysis::{
+        board_value::{validate_args, NextBoardStatus},
+        BoardValue, BoardValueTree,
+    },
+    error,
+    game::{GameRule, Judge},
+    ActionContainer, ActionsFwd, Board, Color,
+};
+
+/// Creates an [`BoardValueTree`] that describes a forced win
+/// by searching only forcing actions (like "tsume" puzzles of shogi).
+///
+/// In the search, `player` (the attacker) performs only actions
+/// that win immediately or create a threat,
+/// i.e., after which the attacker can surround the opponent's boss
+/// in the next turn if the defender ignores it
+/// (see [`Board::winning_actions`]).
+/// The defender tries all legal actions to answer the threat.
+/// Since the attacker has much fewer actions than those in
+/// [`create_checkmate_tree`](`super::create_checkmate_tree`),
+/// long forced wins are proven much faster.
+///
+/// If a forced win within `max_depth` turns is found,
+/// the value of the returned tree is `Win(n)`,
+/// where n is the length of the shortest win consisting of forcing actions.
+/// The tree is pruned in the same way as
+/// [`create_checkmate_tree`](`super::create_checkmate_tree`):
+/// the attacker performs the fastest forcing actions
+/// and the defender performs the actions losing most slowly.
+/// Note that the value of `board` may be greater than `Win(n)`
+/// because the attacker may win faster by non-forcing actions.
+/// If no forced win is found, the value of the returned tree is `Unknown`
+/// and it has no children.
+///
+/// # Errors
+/// Returns `Err` only when the argument is invalid. Specifically,
+/// the following cases are invalid:
+/// - `board` is already finished (at least one boss is surrounded)
+///
+/// # Examples
+/// ```rust
+/// use std::str::FromStr;
+/// use tokyodoves::{BoardBuilder, Color};
+/// use tokyodoves::analysis::{create_forcing_tree, BoardValue};
+/// use tokyodoves::game::GameRule;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let board = BoardBuilder::from_str("A ah;tyHB; b  ; YMm")?.build()?;
+/// let rule = GameRule::new(true);
+/// let tree = create_forcing_tree(board, Color::Red, 15, rule)?;
+/// assert_eq!(*tree.value(), BoardValue::win(11).unwrap());
+/// assert_eq!(tree.depth(), 12);
+/// # Ok(())
+/// # }
+/// ```
+pub fn create_forcing_tree(
+    mut board: Board,
+    player: Color,
+    max_depth: usize,
+    rule: GameRule,
+) -> Result<BoardValueTree, error::Error> {
+    validate_args(board, BoardValue::MAX)?;
+    let mut searcher = ForcingSearcher::new(rule);
+    // Deepening gradually finds short wins without searching long lines
+    for depth in (1..=max_depth).step_by(2) {
+        if let Some(num) = searcher.attack(&mut board, player, depth) {
+            return Ok(searcher.create_attacker_tree(&mut board, player, num));
+        }
+    }
+    Ok(BoardValueTree::new(board, player))
+}
+
+struct ForcingSearcher {
+    rule: GameRule,
+    // Whether surrounding both bosses is a win of the player who does it
+    includes_both: bool,
+    // (board, attacker) -> (searched depth, length of the shortest forced win)
+    table: HashMap<(u64, Color), (usize, Option<usize>)>,
+}
+
+impl ForcingSearcher {
+    fn new(rule: GameRule) -> Self {
+        Self {
+            rule,
+            includes_both: matches!(rule.suicide_atk_judge(), Judge::LastWins),
+            table: HashMap::new(),
+        }
+    }
+
+    fn legal_actions(&self, board: &Board, player: Color) -> ActionsFwd {
+        board.legal_actions(player, true, true, *self.rule.is_remove_accepted())
+    }
+
+    fn is_threat(&self, board: &Board, player: Color) -> bool {
+        !board
+            .surrounding_actions(player, self.includes_both)
+            .is_empty()
+    }
+
+    /// Returns the length of the shortest forced win of `player` within `depth` turns.
+    fn attack(&mut self, board: &mut Board, player: Color, depth: usize) -> Option<usize> {
+        if depth == 0 {
+            return None;
+        }
+        let key = (board.to_u64(), player);
+        match self.table.get(&key) {
+            Some(&(_, Some(num))) => return (num <= depth).then_some(num),
+            Some(&(searched, None)) if depth <= searched => return None,
+            _ => (),
+        }
+
+        let mut best = None;
+        if self.is_threat(board, player) {
+            best = Some(1);
+        } else if depth >= 3 {
+            for action in self.legal_actions(board, player) {
+                // Only wins faster than the best one found so far are searched
+                let next_depth = best.map_or(depth, |num| num - 2) - 1;
+                if next_depth < 2 {
+                    break;
+                }
+                let token = board.make(action);
+                let is_forcing = NextBoardStatus::of(board, player, &self.rule)
+                    == NextBoardStatus::Unknown
+                    && self.is_threat(board, player);
+                if is_forcing {
+                    if let Some(num) = self.defend(board, !player, next_depth) {
+                        best = Some(num + 1);
+                    }
+                }
+                board.unmake(token);
+            }
+        }
+        self.table.insert(key, (depth, best));
+        best
+    }
+
+    /// Returns the length of the slowest loss of `player` within `depth` turns
+    /// if every answer of `player` loses.
+    fn defend(&mut self, board: &mut Board, player: Color, depth: usize) -> Option<usize> {
+        use NextBoardStatus::*;
+        let mut worst = 2;
+        for action in self.legal_actions(board, player) {
+            let token = board.make(action);
+            let num = match NextBoardStatus::of(board, player, &self.rule) {
+                Win | Draw => None,
+                Lose => Some(0),
+                Unknown => self.attack(board, !player, depth - 1),
+            };
+            board.unmake(token);
+            worst = worst.max(num? + 1);
+        }
+        Some(worst)
+    }
+
+    fn create_attacker_tree(
+        &mut self,
+        board: &mut Board,
+        player: Color,
+        num: usize,
+    ) -> BoardValueTree {
+        let mut tree = BoardValueTree::new(*board, player);
+        tree.value = BoardValue::win(num).unwrap();
+        for action in self.legal_actions(board, player) {
+            let token = board.make(action);
+            match NextBoardStatus::of(board, player, &self.rule) {
+                NextBoardStatus::Win if num == 1 => {
+                    let mut child = BoardValueTree::new(*board, !player);
+                    child.value = BoardValue::finished();
+                    tree.actions2children.insert(action, child);
+                }
+                NextBoardStatus::Unknown
+                    if num > 1
+                        && self.is_threat(board, player)
+                        && self.defend(board, !player, num - 1) == Some(num - 1) =>
+                {
+                    let child = self.create_defender_tree(board, !player, num - 1);
+                    tree.actions2children.insert(action, child);
+                }
+                _ => (),
+            }
+            board.unmake(token);
+        }
+        tree
+    }
+
+    fn create_defender_tree(
+        &mut self,
+        board: &mut Board,
+        player: Color,
+        num: usize,
+    ) -> BoardValueTree {
+        let mut tree = BoardValueTree::new(*board, player);
+        tree.value = BoardValue::lose(num).unwrap();
+        for action in self.legal_actions(board, player) {
+            let token = board.make(action);
+            if NextBoardStatus::of(board, player, &self.rule) == NextBoardStatus::Unknown
+                && self.attack(board, !player, num - 1) == Some(num - 1)
+            {
+                let child = self.create_attacker_tree(board, !player, num - 1);
+                tree.actions2children.insert(action, child);
+            }
+            board.unmake(token);
+        }
+        tree
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        analysis::{compare_board_value, create_checkmate_tree},
+        BoardBuilder,
+    };
+    use std::cmp::Ordering;
+    use std::str::FromStr;
+
+    // Checks that the attacker wins whatever the defender does in the tree
+    fn assert_forced_win(tree: &BoardValueTree, rule: GameRule) {
+        let mut board = tree.board();
+        let player = *tree.player();
+        let value = *tree.value();
+        if value.is_win() {
+            assert!(!tree.is_leaf());
+            for (action, child) in tree.actions_children() {
+                let next_board = board.perform_unchecked_copied(*action);
+                assert_eq!(child.board(), next_board);
+                if value == BoardValue::MAX {
+                    assert!(child.value().is_finished());
+                } else {
+                    assert_eq!(*child.value(), value.try_decrement().unwrap());
+                    assert_forced_win(child, rule);
+                }
+            }
+        } else {
+            let mut searcher = ForcingSearcher::new(rule);
+            let num = value.unwrap();
+            assert_eq!(searcher.defend(&mut board, player, num), Some(num));
+            for child in tree.children() {
+                assert_eq!(*child.value(), value.try_decrement().unwrap());
+                assert_forced_win(child, rule);
+            }
+        }
+    }
+
+    #[test]
+    fn test_forcing_tree() {
+        let rule = GameRule::new(true).with_suicide_atk_judge(Judge::NextWins);
+        let board_value = [
+            (" B; a;TH y;b mM", 5),
+            (" By;H  a;A m;  Yb", 3),
+            ("bB; H;Y h;  T", 3),
+            ("T; B b;  yY; t", 3),
+            ("hB A;maYT; Htb;M y", 5),
+            ("Ba;  H;  hA;MY b", 5),
+        ];
+        for (s, num) in board_value {
+            let board = BoardBuilder::from_str(s).unwrap().build().unwrap();
+            let tree = create_forcing_tree(board, Color::Red, 9, rule).unwrap();
+            let value = *tree.value();
+            assert!(value.is_win(), "{s}");
+            // Forcing actions cannot win faster than the optimal play
+            assert!(value.unwrap() >= num);
+            let cmp = compare_board_value(board, value, Color::Red, rule).unwrap();
+            assert_ne!(cmp, Ordering::Less);
+            assert_forced_win(&tree, rule);
+
+            if value.unwrap() == num {
+                let expected = create_checkmate_tree(board, Color::Red, num, rule).unwrap();
+                assert_eq!(*expected.value(), value);
+            }
+        }
+
+        // Too deep to be proven by searching all actions in tests
+        let board = BoardBuilder::from_str("A ah;tyHB; b  ; YMm")
+            .unwrap()
+            .build()
+            .unwrap();
+        let tree = create_forcing_tree(board, Color::Red, 15, rule).unwrap();
+        assert_eq!(tree.value().try_unwrap(), Some(11));
+        assert_eq!(tree.depth(), 12);
+        assert_forced_win(&tree, rule);
+
+        // Compatible with the outputs of other trees
+        assert!(tree.to_string().contains(&tree.value().to_string()));
+        let mut dot = Vec::new();
+        tree.save_as_dot(&mut dot).unwrap();
+        assert!(!dot.is_empty());
+    }
+
+    #[test]
+    fn test_forcing_tree_not_found() {
+        let rule = GameRule::new(true);
+        let tree = create_forcing_tree(Board::new(), Color::Red, 7, rule).unwrap();
+        assert!(tree.value().is_unknown());
+        assert!(tree.is_leaf());
+
+        // Red wins in 5 turns but the first action creates no threat
+        let board = BoardBuilder::from_str("bB;T YA").unwrap().build().unwrap();
+        let tree = create_forcing_tree(board, Color::Red, 9, rule).unwrap();
+        assert!(tree.value().is_unknown());
+    }
+}
@@ -0,0 +1,472 @@
+use crate::{
+    analysis::{
+        board_value::{for_each_next_board, validate_args, NextBoardStatus, Searcher},
+        BoardValue, TranspositionTable,
+    },
+    error,
+    game::GameRule,
+    Action, Board, Color, Dove,
+};
+
+/// The score of a board where the player wins right now.
+///
+/// A board where the player wins in n turns is scored as `MATE_SCORE - n`,
+/// and one where the player loses in n turns as `-(MATE_SCORE - n)`.
+/// Scores returned by [`Evaluator`]s are clamped into
+/// the range `-MATE_SCORE / 2..=MATE_SCORE / 2` so that they are distinguished from them.
+pub const MATE_SCORE: i32 = 1_000_000;
+
+/// A static evaluation function of [`Board`].
+///
+/// It is used by [`score_actions`] and [`find_best_actions_with_evaluator`]
+/// to compare boards whose values are not determined by searching.
+/// Any function or closure with a signature `Fn(&Board, Color) -> i32` implements this trait.
+///
+/// # Examples
+/// ```rust
+/// use tokyodoves::{Board, Color};
+/// use tokyodoves::analysis::Evaluator;
+///
+/// let evaluator = |board: &Board, player: Color| {
+///     board.count_doves_on_field_of(player) as i32
+///         - board.count_doves_on_field_of(!player) as i32
+/// };
+/// assert_eq!(evaluator.evaluate(&Board::new(), Color::Red), 0);
+/// ```
+pub trait Evaluator {
+    /// Returns the score of `board` from the viewpoint of `player`,
+    /// who is going to perform the next action.
+    ///
+    /// The greater the score is, the better the board is for `player`.
+    /// It is expected that the score for the opponent is the negative of the score for `player`.
+    fn evaluate(&self, board: &Board, player: Color) -> i32;
+}
+
+impl<F> Evaluator for F
+where
+    F: Fn(&Board, Color) -> i32,
+{
+    fn evaluate(&self, board: &Board, player: Color) -> i32 {
+        self(board, player)
+    }
+}
+
+/// The default implementation of [`Evaluator`].
+///
+/// The score is a weighted sum of the differences between the player and the opponent
+/// in the following features:
+/// - liberty of the boss ([`Board::liberty_of_boss`])<br>
+///     The boss is surrounded when its liberty becomes zero.
+/// - total liberty of doves other than the boss ([`Board::liberty`])
+/// - number of doves on the field ([`Board::count_doves_on_field_of`])
+/// - number of doves in the hand ([`Board::doves_in_hand_of`])
+///
+/// # Examples
+/// ```rust
+/// use std::str::FromStr;
+/// use tokyodoves::{BoardBuilder, Color};
+/// use tokyodoves::analysis::{DefaultEvaluator, Evaluator};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let board = BoardBuilder::from_str("bT; B; aHM;  m")?.build()?;
+/// let evaluator = DefaultEvaluator::new();
+/// let score = evaluator.evaluate(&board, Color::Red);
+/// assert_eq!(score, -evaluator.evaluate(&board, Color::Green));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DefaultEvaluator {
+    boss_liberty_weight: i32,
+    liberty_weight: i32,
+    doves_on_field_weight: i32,
+    doves_in_hand_weight: i32,
+}
+
+impl Default for DefaultEvaluator {
+    fn default() -> Self {
+        Self {
+            boss_liberty_weight: 100,
+            liberty_weight: 5,
+            doves_on_field_weight: 10,
+            doves_in_hand_weight: 5,
+        }
+    }
+}
+
+impl DefaultEvaluator {
+    /// Creates an evaluator with default weights.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a reference to the weight of the liberty of the boss.
+    pub fn boss_liberty_weight(&self) -> &i32 {
+        &self.boss_liberty_weight
+    }
+
+    /// Returns a reference to the weight of the total liberty of doves other than the boss.
+    pub fn liberty_weight(&self) -> &i32 {
+        &self.liberty_weight
+    }
+
+    /// Returns a reference to the weight of the number of doves on the field.
+    pub fn doves_on_field_weight(&self) -> &i32 {
+        &self.doves_on_field_weight
+    }
+
+    /// Returns a reference to the weight of the number of doves in the hand.
+    pub fn doves_in_hand_weight(&self) -> &i32 {
+        &self.doves_in_hand_weight
+    }
+
+    /// Returns a new evaluator with the specified weight of the liberty of the boss.
+    pub fn with_boss_liberty_weight(self, weight: i32) -> Self {
+        Self {
+            boss_liberty_weight: weight,
+            ..self
+        }
+    }
+
+    /// Returns a new evaluator with the specified weight
+    /// of the total liberty of doves other than the boss.
+    pub fn with_liberty_weight(self, weight: i32) -> Self {
+        Self {
+            liberty_weight: weight,
+            ..self
+        }
+    }
+
+    /// Returns a new evaluator with the specified weight of the number of doves on the field.
+    pub fn with_doves_on_field_weight(self, weight: i32) -> Self {
+        Self {
+            doves_on_field_weight: weight,
+            ..self
+        }
+    }
+
+    /// Returns a new evaluator with the specified weight of the number of doves in the hand.
+    pub fn with_doves_in_hand_weight(self, weight: i32) -> Self {
+        Self {
+            doves_in_hand_weight: weight,
+            ..self
+        }
+    }
+
+    fn evaluate_one_side(&self, board: &Board, player: Color) -> i32 {
+        let liberty: usize = board
+            .doves_on_field_of(player)
+            .into_iter()
+            .filter(|dove| *dove != Dove::B)
+            .filter_map(|dove| board.liberty(player, dove))
+            .sum();
+        self.boss_liberty_weight * board.liberty_of_boss(player) as i32
+            + self.liberty_weight * liberty as i32
+            + self.doves_on_field_weight * board.count_doves_on_field_of(player) as i32
+            + self.doves_in_hand_weight * board.doves_in_hand_of(player).len() as i32
+    }
+}
+
+impl Evaluator for DefaultEvaluator {
+    fn evaluate(&self, board: &Board, player: Color) -> i32 {
+        self.evaluate_one_side(board, player) - self.evaluate_one_side(board, !player)
+    }
+}
+
+/// Scores all legal [`Action`]s by a depth-limited alpha-beta search.
+///
+/// Each action is scored by the negamax value of the board after it,
+/// which is searched until `search_depth` turns in total including the action itself.
+/// Boards at the end of the search are scored by `evaluator`,
+/// while finished games are scored according to [`MATE_SCORE`]
+/// (a draw is scored as zero).
+/// Actions are sorted in descending order of their scores.
+///
+/// # Errors
+/// Returns `Err` only when `board` is already finished (at least one boss is surrounded).
+///
+/// # Examples
+/// ```rust
+/// use std::str::FromStr;
+/// use tokyodoves::{BoardBuilder, Color};
+/// use tokyodoves::analysis::{score_actions, DefaultEvaluator, MATE_SCORE};
+/// use tokyodoves::game::GameRule;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let board = BoardBuilder::from_str("bB;T YA")?.build()?;
+/// let rule = GameRule::new(true);
+/// let scores = score_actions(board, Color::Red, 5, rule, &DefaultEvaluator::new())?;
+/// assert_eq!(scores[0].1, MATE_SCORE - 5); // win in 5 turns
+/// # Ok(())
+/// # }
+/// ```
+pub fn score_actions<E>(
+    mut board: Board,
+    player: Color,
+    search_depth: usize,
+    rule: GameRule,
+    evaluator: &E,
+) -> Result<Vec<(Action, i32)>, error::Error>
+where
+    E: Evaluator + ?Sized,
+{
+    validate_args(board, BoardValue::MAX)?;
+    let actions: Vec<Action> = board
+        .legal_actions(player, true, true, *rule.is_remove_accepted())
+        .into_iter()
+        .collect();
+    let mut scores =
+        AlphaBeta::new(rule, evaluator).score_actions(&mut board, player, search_depth, &actions);
+    scores.sort_by(|(_, x), (_, y)| y.cmp(x));
+    Ok(scores)
+}
+
+/// Collects the best [`Action`]s by [`BoardValue`] and a heuristic score.
+///
+/// If the value of `board` is determined by searching `search_depth` turns,
+/// it returns the same actions as [`find_best_actions`](`super::find_best_actions`).
+/// Otherwise, it scores the actions returned by [`find_best_actions`](`super::find_best_actions`),
+/// i.e., actions which are not proved to be worse than the others,
+/// in the same way as [`score_actions`] and returns those with the highest score.
+///
+/// # Errors
+/// Returns `Err` only when `board` is already finished (at least one boss is surrounded).
+///
+/// # Examples
+/// ```rust
+/// use tokyodoves::{Board, Color};
+/// use tokyodoves::analysis::{find_best_actions_with_evaluator, DefaultEvaluator};
+/// use tokyodoves::game::GameRule;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let board = Board::new();
+/// let rule = GameRule::new(true);
+/// let evaluator = DefaultEvaluator::new();
+/// let actions = find_best_actions_with_evaluator(board, Color::Red, 2, rule, &evaluator)?;
+/// assert!(!actions.is_empty());
+/// # Ok(())
+/// # }
+/// ```
+pub fn find_best_actions_with_evaluator<E>(
+    mut board: Board,
+    player: Color,
+    search_depth: usize,
+    rule: GameRule,
+    evaluator: &E,
+) -> Result<Vec<Action>, error::Error>
+where
+    E: Evaluator + ?Sized,
+{
+    validate_args(board, BoardValue::MAX)?;
+    let mut table = TranspositionTable::new();
+    let mut searcher = Searcher::new(rule).with_table(&mut table);
+    let is_determined = search_depth > 0
+        && searcher
+            .evaluate(&mut board, player, search_depth)
+            .single()
+            .is_some();
+    let candidates = searcher.find_best_actions(&mut board, player, search_depth);
+    if is_determined || candidates.len() <= 1 {
+        return Ok(candidates);
+    }
+
+    let scores = AlphaBeta::new(rule, evaluator).score_actions(
+        &mut board,
+        player,
+        search_depth,
+        &candidates,
+    );
+    let max_score = scores.iter().map(|(_, score)| *score).max();
+    Ok(scores
+        .into_iter()
+        .filter(|(_, score)| Some(*score) == max_score)
+        .map(|(action, _)| action)
+        .collect())
+}
+
+/// A context of alpha-beta search
+struct AlphaBeta<'a, E: ?Sized> {
+    rule: GameRule,
+    evaluator: &'a E,
+}
+
+impl<'a, E> AlphaBeta<'a, E>
+where
+    E: Evaluator + ?Sized,
+{
+    fn new(rule: GameRule, evaluator: &'a E) -> Self {
+        Self { rule, evaluator }
+    }
+
+    /// Scores `actions` with the full window so that the scores are exact.
+    fn score_actions(
+        &self,
+        board: &mut Board,
+        player: Color,
+        search_depth: usize,
+        actions: &[Action],
+    ) -> Vec<(Action, i32)> {
+        let mut scores = Vec::new();
+        for_each_next_board(board, player, self.rule, |action, next_board, status| {
+            if actions.contains(&action) {
+                let score = self.score_next(
+                    next_board,
+                    status,
+                    player,
+                    search_depth,
+                    0,
+                    -MATE_SCORE,
+                    MATE_SCORE,
+                );
+                scores.push((action, score));
+            }
+        });
+        scores
+    }
+
+    /// Returns the score of the board after an action performed at `ply`.
+    #[allow(clippy::too_many_arguments)]
+    fn score_next(
+        &self,
+        next_board: &mut Board,
+        status: NextBoardStatus,
+        player: Color,
+        depth: usize,
+        ply: i32,
+        alpha: i32,
+        beta: i32,
+    ) -> i32 {
+        use NextBoardStatus::*;
+        match status {
+            Win => MATE_SCORE - (ply + 1),
+            Lose => -(MATE_SCORE - (ply + 1)),
+            Draw => 0,
+            Unknown => -self.negamax(
+                next_board,
+                !player,
+                depth.saturating_sub(1),
+                ply + 1,
+                -beta,
+                -alpha,
+            ),
+        }
+    }
+
+    fn negamax(
+        &self,
+        board: &mut Board,
+        player: Color,
+        depth: usize,
+        ply: i32,
+        mut alpha: i32,
+        beta: i32,
+    ) -> i32 {
+        if depth == 0 {
+            let bound = MATE_SCORE / 2;
+            return self.evaluator.evaluate(board, player).clamp(-bound, bound);
+        }
+
+        let mut children = Vec::new();
+        for_each_next_board(board, player, self.rule, |action, next_board, status| {
+            // Searching promising boards first makes more cutoffs
+            let key = match status {
+                NextBoardStatus::Unknown if depth >= 2 => {
+                    self.evaluator.evaluate(next_board, !player)
+                }
+                _ => 0,
+            };
+            children.push((action, status, key));
+        });
+        if children
+            .iter()
+            .any(|(_, status, _)| *status == NextBoardStatus::Win)
+        {
+            return MATE_SCORE - (ply + 1);
+        }
+        children.sort_by_key(|(_, _, key)| *key);
+
+        let mut best = -MATE_SCORE;
+        for (action, status, _) in children {
+            let token = board.make(action);
+            let score = self.score_next(board, status, player, depth, ply, alpha, beta);
+            board.unmake(token);
+            best = best.max(score);
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{analysis, game::Judge, BoardBuilder};
+    use std::str::FromStr;
+
+    #[test]
+    fn test_default_evaluator_is_antisymmetric() {
+        let evaluator = DefaultEvaluator::new();
+        for s in [" B; a;TH y;b mM", "bB;T YA", "b;B", "bT; B; aHM;  m"] {
+            let board = BoardBuilder::from_str(s).unwrap().build().unwrap();
+            assert_eq!(
+                evaluator.evaluate(&board, Color::Red),
+                -evaluator.evaluate(&board, Color::Green)
+            );
+        }
+    }
+
+    #[test]
+    fn test_score_actions_finds_mate() {
+        let evaluator = DefaultEvaluator::new();
+        let board_value = [
+            (" B; a;TH y;b mM", 5),
+            (" By;H  a;A m;  Yb", 3),
+            ("bB; H;Y h;  T", 3),
+            ("bB;T YA", 5),
+        ];
+        for rule in [
+            GameRule::new(true),
+            GameRule::new(true).with_suicide_atk_judge(Judge::Draw),
+        ] {
+            for (s, num) in board_value {
+                let board = BoardBuilder::from_str(s).unwrap().build().unwrap();
+                let scores = score_actions(board, Color::Red, num, rule, &evaluator).unwrap();
+                let mut mates: Vec<Action> = scores
+                    .iter()
+                    .filter(|(_, score)| *score == MATE_SCORE - num as i32)
+                    .map(|(action, _)| *action)
+                    .collect();
+                let mut best = analysis::find_best_actions(board, Color::Red, num, rule).unwrap();
+                mates.sort_by_key(|a| format!("{a:?}"));
+                best.sort_by_key(|a| format!("{a:?}"));
+                assert_eq!(mates, best);
+
+                let best_with_evaluator =
+                    find_best_actions_with_evaluator(board, Color::Red, num, rule, &evaluator)
+                        .unwrap();
+                assert_eq!(best_with_evaluator.len(), best.len());
+            }
+        }
+    }
+
+    #[test]
+    fn test_find_best_actions_with_evaluator() {
+        let rule = GameRule::new(true);
+        let evaluator = |board: &Board, player: Color| {
+            board.liberty_of_boss(player) as i32 - board.liberty_of_boss(!player) as i32
+        };
+        for s in ["b;B", " B; a;TH y;b mM", "hB A;maYT; Htb;M y"] {
+            let board = BoardBuilder::from_str(s).unwrap().build().unwrap();
+            for player in [Color::Red, Color::Green] {
+                let best = analysis::find_best_actions(board, player, 2, rule).unwrap();
+                let best_with_evaluator =
+                    find_best_actions_with_evaluator(board, player, 2, rule, &evaluator).unwrap();
+                assert!(!best_with_evaluator.is_empty());
+                assert!(best_with_evaluator.iter().all(|a| best.contains(a)));
+            }
+        }
+    }
+}
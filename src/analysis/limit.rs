@@ -0,0 +1,700 @@
+use std::cmp::Ordering;
+use std::sync::{
+    atomic::{AtomicBool, Ordering as AtomicOrdering},
+    Arc,
+};
+use std::time::{Duration, Instant};
+
+use crate::{
+    analysis::{
+        board_value::{create_checkmate_tree_unchecked, validate_args, Searcher},
+        BoardValue, BoardValueTree, Interval, TranspositionTable,
+    },
+    error,
+    game::GameRule,
+    Action, Board, Color,
+};
+
+/// Conditions to interrupt a search before it reaches the maximum depth.
+///
+/// A search is interrupted when the deadline has passed
+/// or the cancellation flag has been set to `true`, whichever comes first.
+/// A limit without any condition never interrupts a search.
+///
+/// It is received by [`evaluate_board_with_limit`], [`find_best_actions_with_limit`]
+/// and [`create_checkmate_tree_with_limit`].
+///
+/// # Examples
+/// ```rust
+/// use std::sync::{atomic::{AtomicBool, Ordering}, Arc};
+/// use std::time::Duration;
+/// use tokyodoves::analysis::SearchLimit;
+///
+/// let cancel_flag = Arc::new(AtomicBool::new(false));
+/// let limit = SearchLimit::new()
+///     .with_time_limit(Duration::from_secs(10))
+///     .with_cancel_flag(Arc::clone(&cancel_flag));
+/// assert!(!limit.is_reached());
+/// cancel_flag.store(true, Ordering::Relaxed); // e.g. from another thread
+/// assert!(limit.is_reached());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SearchLimit {
+    deadline: Option<Instant>,
+    cancel_flag: Option<Arc<AtomicBool>>,
+}
+
+impl SearchLimit {
+    /// Creates a limit without any condition.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a reference to the deadline of the search.
+    pub fn deadline(&self) -> &Option<Instant> {
+        &self.deadline
+    }
+
+    /// Returns a reference to the flag to cancel the search.
+    pub fn cancel_flag(&self) -> &Option<Arc<AtomicBool>> {
+        &self.cancel_flag
+    }
+
+    /// Returns a new limit with the specified deadline.
+    pub fn with_deadline(self, deadline: Instant) -> Self {
+        Self {
+            deadline: Some(deadline),
+            ..self
+        }
+    }
+
+    /// Returns a new limit whose deadline is `time_limit` after now.
+    pub fn with_time_limit(self, time_limit: Duration) -> Self {
+        self.with_deadline(Instant::now() + time_limit)
+    }
+
+    /// Returns a new limit with the specified flag.
+    ///
+    /// The search is cancelled once `true` is stored in the flag.
+    pub fn with_cancel_flag(self, cancel_flag: Arc<AtomicBool>) -> Self {
+        Self {
+            cancel_flag: Some(cancel_flag),
+            ..self
+        }
+    }
+
+    /// Returns `true` if the cancellation flag is set or the deadline has passed.
+    pub fn is_reached(&self) -> bool {
+        self.is_cancelled() || self.is_expired()
+    }
+
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancel_flag
+            .as_ref()
+            .is_some_and(|flag| flag.load(AtomicOrdering::Relaxed))
+    }
+
+    pub(crate) fn is_expired(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+}
+
+/// Statistics of a search.
+///
+/// It is contained in [`LimitedResult`] and can be used
+/// to observe the progress of a search or to compare performances.
+/// A node is a board whose next boards are generated during the search.
+/// Boards whose comparisons are found in tables are not counted as nodes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchStats {
+    nodes: u64,
+    cutoffs: u64,
+    table_hits: u64,
+    max_ply: usize,
+    elapsed: Duration,
+    /// The ply of the node being searched
+    ply: usize,
+}
+
+impl SearchStats {
+    /// Returns the number of nodes visited.
+    pub fn nodes(&self) -> u64 {
+        self.nodes
+    }
+
+    /// Returns the number of nodes whose remaining next boards were skipped
+    /// because the result had been already determined.
+    pub fn cutoffs(&self) -> u64 {
+        self.cutoffs
+    }
+
+    /// Returns the number of comparisons answered by a [`TranspositionTable`]
+    /// or a [`Tablebase`](`super::Tablebase`) without searching.
+    pub fn table_hits(&self) -> u64 {
+        self.table_hits
+    }
+
+    /// Returns the maximum number of turns from the root to a node visited.
+    pub fn max_ply(&self) -> usize {
+        self.max_ply
+    }
+
+    /// Returns the time elapsed since the search started.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Returns the number of nodes visited per second.
+    pub fn nodes_per_second(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 {
+            self.nodes as f64 / secs
+        } else {
+            0.0
+        }
+    }
+
+    pub(crate) fn enter(&mut self) {
+        self.nodes += 1;
+        self.ply += 1;
+        self.max_ply = self.max_ply.max(self.ply);
+    }
+
+    pub(crate) fn leave(&mut self) {
+        self.ply -= 1;
+    }
+
+    pub(crate) fn count_cutoff(&mut self) {
+        self.cutoffs += 1;
+    }
+
+    pub(crate) fn count_table_hit(&mut self) {
+        self.table_hits += 1;
+    }
+
+    fn with_elapsed(self, elapsed: Duration) -> Self {
+        Self { elapsed, ..self }
+    }
+}
+
+/// A result of a search that may have been interrupted by a [`SearchLimit`].
+///
+/// The search deepens one turn at a time and the result of the deepest completed
+/// search is kept, so it is as valid as the result of the non-limited function
+/// called with [`depth`](`LimitedResult::depth`) as the search depth.
+#[derive(Debug, Clone)]
+pub struct LimitedResult<T> {
+    result: T,
+    depth: usize,
+    is_interrupted: bool,
+    stats: SearchStats,
+}
+
+impl<T> LimitedResult<T> {
+    fn new(result: T) -> Self {
+        Self {
+            result,
+            depth: 0,
+            is_interrupted: false,
+            stats: SearchStats::default(),
+        }
+    }
+
+    /// Returns a reference to the result of the deepest completed search.
+    pub fn result(&self) -> &T {
+        &self.result
+    }
+
+    /// Consumes `self` and returns the result of the deepest completed search.
+    pub fn into_result(self) -> T {
+        self.result
+    }
+
+    /// Returns the depth of the deepest completed search.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns `true` if the search was interrupted by the limit
+    /// before reaching the maximum depth or determining the value.
+    pub fn is_interrupted(&self) -> bool {
+        self.is_interrupted
+    }
+
+    /// Returns a reference to the statistics of the whole search
+    /// including the interrupted part.
+    pub fn stats(&self) -> &SearchStats {
+        &self.stats
+    }
+
+    fn update(&mut self, result: T, depth: usize) {
+        self.result = result;
+        self.depth = depth;
+    }
+}
+
+/// A callback invoked every time a search of some depth is completed
+type ProgressCallback<'a> = Box<dyn FnMut(&LimitedResult<Interval>) + 'a>;
+
+/// Options of a search by [`evaluate_board_with_options`] and [`find_best_actions_with_options`].
+///
+/// # Examples
+/// ```rust
+/// use std::time::Duration;
+/// use tokyodoves::{Board, Color};
+/// use tokyodoves::analysis::{
+///     find_best_actions_with_options, SearchLimit, SearchOptions, TranspositionTable,
+/// };
+/// use tokyodoves::game::GameRule;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut table = TranspositionTable::new();
+/// let options = SearchOptions::new()
+///     .with_limit(SearchLimit::new().with_time_limit(Duration::from_millis(100)))
+///     .with_table(&mut table)
+///     .with_progress(|progress| {
+///         let stats = progress.stats();
+///         println!(
+///             "depth {} value {} nodes {} nps {:.0}",
+///             progress.depth(),
+///             progress.result(),
+///             stats.nodes(),
+///             stats.nodes_per_second(),
+///         );
+///     });
+/// let rule = GameRule::new(true);
+/// let result = find_best_actions_with_options(Board::new(), Color::Red, 5, rule, options)?;
+/// assert!(!result.result().1.is_empty());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default)]
+pub struct SearchOptions<'a> {
+    limit: SearchLimit,
+    table: Option<&'a mut TranspositionTable>,
+    progress: Option<ProgressCallback<'a>>,
+}
+
+impl<'a> std::fmt::Debug for SearchOptions<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SearchOptions")
+            .field("limit", &self.limit)
+            .field("table", &self.table.is_some())
+            .field("progress", &self.progress.is_some())
+            .finish()
+    }
+}
+
+impl<'a> SearchOptions<'a> {
+    /// Creates options without any limit, table or callback.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a reference to the limit of the search.
+    pub fn limit(&self) -> &SearchLimit {
+        &self.limit
+    }
+
+    /// Returns new options with the specified limit.
+    pub fn with_limit(self, limit: SearchLimit) -> Self {
+        Self { limit, ..self }
+    }
+
+    /// Returns new options with a [`TranspositionTable`] used in the search.
+    ///
+    /// Results of comparisons are read from and written to `table`,
+    /// so it can be reused by the next search.
+    /// A new table is created for each search if it is not specified.
+    pub fn with_table(self, table: &'a mut TranspositionTable) -> Self {
+        Self {
+            table: Some(table),
+            ..self
+        }
+    }
+
+    /// Returns new options with a callback invoked every time a search of some depth is completed.
+    ///
+    /// The callback receives the [`Interval`] of the depth with statistics so far.
+    pub fn with_progress<F>(self, progress: F) -> Self
+    where
+        F: FnMut(&LimitedResult<Interval>) + 'a,
+    {
+        Self {
+            progress: Some(Box::new(progress)),
+            ..self
+        }
+    }
+
+    fn report(&mut self, interval: Interval, depth: usize, stats: SearchStats) {
+        if let Some(progress) = self.progress.as_mut() {
+            progress(&LimitedResult {
+                result: interval,
+                depth,
+                is_interrupted: false,
+                stats,
+            });
+        }
+    }
+}
+
+/// Calculates a possible range of [`BoardValue`] of specified [`Board`]
+/// until `limit` is reached.
+///
+/// It searches deeper one turn at a time up to `max_depth` turns
+/// and returns the [`Interval`] of the deepest completed search,
+/// which is the same as the one returned by [`evaluate_board`](`super::evaluate_board`)
+/// with that depth.
+/// The search finishes before `max_depth` once the value of `board` is determined.
+///
+/// # Errors
+/// Returns `Err` only when the argument is invalid. Specifically,
+/// the following cases are invalid:
+/// - `board` is already finished (at least one boss is surrounded)
+///
+/// # Examples
+/// ```rust
+/// use std::str::FromStr;
+/// use std::sync::{atomic::AtomicBool, Arc};
+/// use tokyodoves::{BoardBuilder, Color};
+/// use tokyodoves::analysis::{evaluate_board_with_limit, BoardValue, SearchLimit};
+/// use tokyodoves::game::GameRule;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let board = BoardBuilder::from_str("bB;T YA")?.build()?;
+/// let rule = GameRule::new(true);
+/// // The search is interrupted only when the flag is set to `true`
+/// let limit = SearchLimit::new().with_cancel_flag(Arc::new(AtomicBool::new(false)));
+/// let result = evaluate_board_with_limit(board, Color::Red, 9, rule, &limit)?;
+/// assert_eq!(result.result().single(), BoardValue::win(5));
+/// assert_eq!(result.depth(), 5);
+/// assert!(!result.is_interrupted());
+/// # Ok(())
+/// # }
+/// ```
+pub fn evaluate_board_with_limit(
+    board: Board,
+    player: Color,
+    max_depth: usize,
+    rule: GameRule,
+    limit: &SearchLimit,
+) -> Result<LimitedResult<Interval>, error::Error> {
+    let options = SearchOptions::new().with_limit(limit.clone());
+    evaluate_board_with_options(board, player, max_depth, rule, options)
+}
+
+/// Calculates a possible range of [`BoardValue`] of specified [`Board`]
+/// with [`SearchOptions`].
+///
+/// This function behaves like [`evaluate_board_with_limit`]
+/// with the limit, the table and the progress callback of `options`.
+/// The callback is invoked every time a search of some depth is completed.
+///
+/// # Errors
+/// Returns `Err` in the same cases as [`evaluate_board_with_limit`].
+pub fn evaluate_board_with_options(
+    mut board: Board,
+    player: Color,
+    max_depth: usize,
+    rule: GameRule,
+    mut options: SearchOptions,
+) -> Result<LimitedResult<Interval>, error::Error> {
+    validate_args(board, BoardValue::MAX)?;
+    let started = Instant::now();
+    let mut new_table = TranspositionTable::new();
+    let table = options.table.take().unwrap_or(&mut new_table);
+    let limit = options.limit.clone();
+    let mut searcher = Searcher::new(rule).with_table(table).with_limit(&limit);
+    let mut result = LimitedResult::new(Interval::beyond(0));
+    for depth in 1..=max_depth {
+        let value = BoardValue::from(Some(depth));
+        let cmp = searcher.compare(&mut board, value, player);
+        if searcher.is_stopped() {
+            result.is_interrupted = true;
+            break;
+        }
+        let interval = if cmp == Ordering::Equal {
+            Interval::new(value, value)
+        } else {
+            Interval::beyond(depth)
+        };
+        result.update(interval, depth);
+        options.report(
+            interval,
+            depth,
+            searcher.stats().with_elapsed(started.elapsed()),
+        );
+        if interval.single().is_some() {
+            break;
+        }
+    }
+    result.stats = searcher.stats().with_elapsed(started.elapsed());
+    Ok(result)
+}
+
+/// Collects the best [`Action`]s by [`BoardValue`] until `limit` is reached.
+///
+/// It searches deeper one turn at a time up to `max_depth` turns
+/// and returns a pair of the [`Interval`] and the best actions
+/// of the deepest completed search,
+/// which are the same as those returned by [`evaluate_board`](`super::evaluate_board`)
+/// and [`find_best_actions`](`super::find_best_actions`) with that depth.
+/// The search finishes before `max_depth` once the value of `board` is determined.
+/// If no search is completed, all legal actions are returned.
+///
+/// # Errors
+/// Returns `Err` only when the argument is invalid. Specifically,
+/// the following cases are invalid:
+/// - `board` is already finished (at least one boss is surrounded)
+///
+/// # Examples
+/// ```rust
+/// use std::sync::{atomic::AtomicBool, Arc};
+/// use tokyodoves::{Board, Color};
+/// use tokyodoves::analysis::{find_best_actions_with_limit, SearchLimit};
+/// use tokyodoves::game::GameRule;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let board = Board::new();
+/// let rule = GameRule::new(true);
+/// // A search cancelled in advance completes no depth
+/// let limit = SearchLimit::new().with_cancel_flag(Arc::new(AtomicBool::new(true)));
+/// let result = find_best_actions_with_limit(board, Color::Red, 9, rule, &limit)?;
+/// assert!(result.is_interrupted());
+/// assert_eq!(result.depth(), 0);
+/// assert!(!result.result().1.is_empty());
+/// # Ok(())
+/// # }
+/// ```
+pub fn find_best_actions_with_limit(
+    board: Board,
+    player: Color,
+    max_depth: usize,
+    rule: GameRule,
+    limit: &SearchLimit,
+) -> Result<LimitedResult<(Interval, Vec<Action>)>, error::Error> {
+    let options = SearchOptions::new().with_limit(limit.clone());
+    find_best_actions_with_options(board, player, max_depth, rule, options)
+}
+
+/// Collects the best [`Action`]s by [`BoardValue`] with [`SearchOptions`].
+///
+/// This function behaves like [`find_best_actions_with_limit`]
+/// with the limit, the table and the progress callback of `options`.
+/// The callback is invoked every time a search of some depth is completed,
+/// where the actions of the depth are not determined yet.
+///
+/// # Errors
+/// Returns `Err` in the same cases as [`find_best_actions_with_limit`].
+pub fn find_best_actions_with_options(
+    mut board: Board,
+    player: Color,
+    max_depth: usize,
+    rule: GameRule,
+    mut options: SearchOptions,
+) -> Result<LimitedResult<(Interval, Vec<Action>)>, error::Error> {
+    validate_args(board, BoardValue::MAX)?;
+    let started = Instant::now();
+    let mut new_table = TranspositionTable::new();
+    let table = options.table.take().unwrap_or(&mut new_table);
+    let limit = options.limit.clone();
+    let mut searcher = Searcher::new(rule).with_table(table).with_limit(&limit);
+    let actions = searcher.find_best_actions(&mut board, player, 0);
+    let mut result = LimitedResult::new((Interval::beyond(0), actions));
+    for depth in 1..=max_depth {
+        let interval = searcher.evaluate(&mut board, player, depth);
+        if searcher.is_stopped() {
+            result.is_interrupted = true;
+            break;
+        }
+        options.report(
+            interval,
+            depth,
+            searcher.stats().with_elapsed(started.elapsed()),
+        );
+        let actions = searcher.find_best_actions(&mut board, player, depth);
+        if searcher.is_stopped() {
+            result.is_interrupted = true;
+            break;
+        }
+        result.update((interval, actions), depth);
+        if interval.single().is_some() {
+            break;
+        }
+    }
+    result.stats = searcher.stats().with_elapsed(started.elapsed());
+    Ok(result)
+}
+
+/// Creates an [`BoardValueTree`] that describes routes to ends of the game
+/// until `limit` is reached.
+///
+/// It creates trees deeper one turn at a time up to `max_depth` turns
+/// and returns the tree of the deepest completed search,
+/// which is the same as the one returned by
+/// [`create_checkmate_tree`](`super::create_checkmate_tree`) with that depth.
+/// The search finishes before `max_depth` once the value of `board`
+/// turns out to be win or lose.
+///
+/// # Errors
+/// Returns `Err` only when the argument is invalid. Specifically,
+/// the following cases are invalid:
+/// - `board` is already finished (at least one boss is surrounded)
+///
+/// # Examples
+/// ```rust
+/// use std::str::FromStr;
+/// use std::sync::{atomic::AtomicBool, Arc};
+/// use tokyodoves::{BoardBuilder, Color};
+/// use tokyodoves::analysis::{create_checkmate_tree_with_limit, BoardValue, SearchLimit};
+/// use tokyodoves::game::GameRule;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let board = BoardBuilder::from_str(" By;H  a;A m;  Yb")?.build()?;
+/// let rule = GameRule::new(true);
+/// // The search is interrupted only when the flag is set to `true`
+/// let limit = SearchLimit::new().with_cancel_flag(Arc::new(AtomicBool::new(false)));
+/// let result = create_checkmate_tree_with_limit(board, Color::Red, 9, rule, &limit)?;
+/// assert_eq!(*result.result().value(), BoardValue::win(3).unwrap());
+/// assert_eq!(result.depth(), 3);
+/// assert!(!result.is_interrupted());
+/// # Ok(())
+/// # }
+/// ```
+pub fn create_checkmate_tree_with_limit(
+    mut board: Board,
+    player: Color,
+    max_depth: usize,
+    rule: GameRule,
+    limit: &SearchLimit,
+) -> Result<LimitedResult<BoardValueTree>, error::Error> {
+    validate_args(board, BoardValue::MAX)?;
+    let started = Instant::now();
+    let mut stats = SearchStats::default();
+    let mut result = LimitedResult::new(BoardValueTree::new(board, player));
+    for depth in 1..=max_depth {
+        let Some(tree) =
+            create_checkmate_tree_unchecked(&mut board, player, depth, rule, limit, &mut stats)
+        else {
+            result.is_interrupted = true;
+            break;
+        };
+        let is_determined = tree.value().is_win() || tree.value().is_lose();
+        result.update(tree, depth);
+        if is_determined {
+            break;
+        }
+    }
+    result.stats = stats.with_elapsed(started.elapsed());
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        analysis::{create_checkmate_tree, evaluate_board, find_best_actions},
+        game::Judge,
+        BoardBuilder,
+    };
+    use std::str::FromStr;
+
+    #[test]
+    fn test_limited_search_coincides_with_fixed_depth() {
+        let rule = GameRule::new(true).with_suicide_atk_judge(Judge::NextWins);
+        // Never interrupted so that the results do not depend on the speed of the machine
+        let limit = SearchLimit::new().with_cancel_flag(Arc::new(AtomicBool::new(false)));
+        for (s, depth) in [
+            (" By;H  a;A m;  Yb", 3),
+            ("bB; H;Y h;  T", 4),
+            ("bB;T YA", 7),
+        ] {
+            let board = BoardBuilder::from_str(s).unwrap().build().unwrap();
+            let result =
+                find_best_actions_with_limit(board, Color::Red, depth, rule, &limit).unwrap();
+            let depth = result.depth();
+            let (interval, mut actions) = result.into_result();
+            let mut expected = find_best_actions(board, Color::Red, depth, rule).unwrap();
+            actions.sort_by_key(|a| format!("{a:?}"));
+            expected.sort_by_key(|a| format!("{a:?}"));
+            assert_eq!(actions, expected);
+            assert_eq!(
+                interval,
+                evaluate_board(board, Color::Red, depth, rule).unwrap()
+            );
+
+            let result = evaluate_board_with_limit(board, Color::Red, depth, rule, &limit).unwrap();
+            assert_eq!(*result.result(), interval);
+            assert!(!result.is_interrupted());
+
+            let result =
+                create_checkmate_tree_with_limit(board, Color::Red, depth, rule, &limit).unwrap();
+            let expected = create_checkmate_tree(board, Color::Red, depth, rule).unwrap();
+            assert_eq!(result.result().value(), expected.value());
+            assert_eq!(result.result().depth(), expected.depth());
+        }
+    }
+
+    #[test]
+    fn test_interrupted_search() {
+        let board = Board::new();
+        let rule = GameRule::new(true);
+
+        let cancel_flag = Arc::new(AtomicBool::new(true));
+        let limit = SearchLimit::new().with_cancel_flag(cancel_flag);
+        let result = evaluate_board_with_limit(board, Color::Red, 20, rule, &limit).unwrap();
+        assert!(result.is_interrupted());
+        assert_eq!(result.depth(), 0);
+        let result = create_checkmate_tree_with_limit(board, Color::Red, 20, rule, &limit).unwrap();
+        assert!(result.is_interrupted());
+        assert!(result.result().is_leaf());
+
+        // The initial board is not determined within 20 turns
+        let started = Instant::now();
+        let limit = SearchLimit::new().with_time_limit(Duration::from_millis(200));
+        let result = find_best_actions_with_limit(board, Color::Red, 20, rule, &limit).unwrap();
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(result.is_interrupted());
+        assert!(result.depth() < 20);
+        let (interval, actions) = result.result();
+        assert_eq!(*interval, Interval::beyond(result.depth()));
+        assert!(!actions.is_empty());
+    }
+
+    #[test]
+    fn test_progress_and_stats() {
+        let board = BoardBuilder::from_str("bB;T YA").unwrap().build().unwrap();
+        let rule = GameRule::new(true);
+        let mut progress = Vec::new();
+        let mut table = TranspositionTable::new();
+        let options = SearchOptions::new().with_table(&mut table).with_progress(
+            |p: &LimitedResult<Interval>| progress.push((p.depth(), *p.result(), *p.stats())),
+        );
+        let result = find_best_actions_with_options(board, Color::Red, 9, rule, options).unwrap();
+        assert_eq!(result.depth(), 5);
+        assert_eq!(
+            progress.iter().map(|(d, _, _)| *d).collect::<Vec<_>>(),
+            (1..=5).collect::<Vec<_>>()
+        );
+        assert_eq!(progress[4].1.single(), BoardValue::win(5));
+        assert!(progress
+            .windows(2)
+            .all(|w| w[0].2.nodes() <= w[1].2.nodes() && w[0].2.elapsed() <= w[1].2.elapsed()));
+        let stats = result.stats();
+        assert!(stats.nodes() >= progress[4].2.nodes());
+        assert!(stats.cutoffs() > 0 && stats.cutoffs() <= stats.nodes());
+        assert!(stats.max_ply() >= 5);
+
+        // The table filled by the first search answers the second search
+        let options = SearchOptions::new().with_table(&mut table);
+        let result = evaluate_board_with_options(board, Color::Red, 9, rule, options).unwrap();
+        assert_eq!(result.result().single(), BoardValue::win(5));
+        assert!(result.stats().table_hits() > 0);
+
+        let limit = SearchLimit::new();
+        let result = create_checkmate_tree_with_limit(board, Color::Red, 9, rule, &limit).unwrap();
+        assert!(result.stats().nodes() > 0);
+        assert_eq!(result.stats().max_ply(), 5);
+    }
+}
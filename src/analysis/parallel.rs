@@ -0,0 +1,433 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+use std::ops::ControlFlow;
+use std::sync::{
+    atomic::{AtomicBool, AtomicUsize, Ordering as AtomicOrdering},
+    Mutex,
+};
+
+use crate::{
+    analysis::{
+        board_value::{
+            for_each_next_board, try_for_each_unique_next_board, validate_args, NextBoardStatus,
+            Searcher,
+        },
+        BoardValue, Interval, TranspositionTable,
+    },
+    error,
+    game::{GameRule, Judge},
+    Action, Board, Color,
+};
+
+/// Compares the value of specified [`Board`] to a given [`BoardValue`] with multiple threads.
+///
+/// This function returns the same result as [`compare_board_value`](`super::compare_board_value`).
+/// See the documentation of [`evaluate_board_parallel`] for how threads are used.
+///
+/// # Errors
+/// Returns `Err` in the same cases as [`compare_board_value`](`super::compare_board_value`).
+pub fn compare_board_value_parallel(
+    board: Board,
+    value: BoardValue,
+    player: Color,
+    rule: GameRule,
+    num_threads: usize,
+) -> Result<Ordering, error::Error> {
+    validate_args(board, value)?;
+    Ok(ParallelSearcher::new(rule, num_threads).compare(board, value, player))
+}
+
+/// Calculates a possible range of [`BoardValue`] of specified [`Board`] with multiple threads.
+///
+/// This function returns the same result as [`evaluate_board`](`super::evaluate_board`).
+///
+/// The children of `board`, i.e., boards after one action, are distributed to `num_threads` threads.
+/// If `num_threads` is zero, the number of threads is determined by
+/// [`std::thread::available_parallelism`].
+/// The threads share proven results via a [`TranspositionTable`],
+/// and once one of them finds an action that determines the result,
+/// the other threads stop their searches.
+///
+/// # Errors
+/// Returns `Err` in the same cases as [`evaluate_board`](`super::evaluate_board`).
+///
+/// # Examples
+/// ```rust
+/// use std::str::FromStr;
+/// use tokyodoves::{BoardBuilder, Color};
+/// use tokyodoves::analysis::{evaluate_board, evaluate_board_parallel};
+/// use tokyodoves::game::GameRule;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let board = BoardBuilder::from_str("bB;T YA")?.build()?;
+/// let rule = GameRule::new(true);
+/// let value = evaluate_board_parallel(board, Color::Red, 5, rule, 4)?;
+/// assert_eq!(value, evaluate_board(board, Color::Red, 5, rule)?);
+/// # Ok(())
+/// # }
+/// ```
+pub fn evaluate_board_parallel(
+    board: Board,
+    player: Color,
+    search_depth: usize,
+    rule: GameRule,
+    num_threads: usize,
+) -> Result<Interval, error::Error> {
+    validate_args(board, BoardValue::MAX)?;
+    Ok(ParallelSearcher::new(rule, num_threads).evaluate(board, player, search_depth))
+}
+
+/// Collects the best [`Action`]s by [`BoardValue`] with multiple threads.
+///
+/// This function returns the same result as [`find_best_actions`](`super::find_best_actions`),
+/// including the order of actions.
+/// See the documentation of [`evaluate_board_parallel`] for how threads are used.
+///
+/// # Errors
+/// Returns `Err` in the same cases as [`find_best_actions`](`super::find_best_actions`).
+pub fn find_best_actions_parallel(
+    board: Board,
+    player: Color,
+    search_depth: usize,
+    rule: GameRule,
+    num_threads: usize,
+) -> Result<Vec<Action>, error::Error> {
+    validate_args(board, BoardValue::MAX)?;
+    Ok(ParallelSearcher::new(rule, num_threads).find_best_actions(board, player, search_depth))
+}
+
+/// A searcher that distributes children of the root board to threads
+struct ParallelSearcher {
+    rule: GameRule,
+    /// A table shared by all threads
+    shared_table: Mutex<TranspositionTable>,
+    /// Tables owned by each thread, which are kept during iterative deepening
+    tables: Vec<TranspositionTable>,
+}
+
+impl ParallelSearcher {
+    fn new(rule: GameRule, num_threads: usize) -> Self {
+        let num_threads = if num_threads == 0 {
+            std::thread::available_parallelism().map_or(1, NonZeroUsize::get)
+        } else {
+            num_threads
+        };
+        let mut shared_table = TranspositionTable::new();
+        shared_table.adapt_to(&rule);
+        Self {
+            rule,
+            shared_table: Mutex::new(shared_table),
+            tables: vec![TranspositionTable::new(); num_threads],
+        }
+    }
+
+    /// Searches each of `boards` by `search` in parallel.
+    ///
+    /// Once `stops_on` returns `true` for a result, all threads stop,
+    /// and results of searches not completed are `None`.
+    fn search_each<T, S, P>(&mut self, boards: &[Board], search: S, stops_on: P) -> Vec<Option<T>>
+    where
+        T: Send,
+        S: Fn(&mut Searcher, &mut Board) -> T + Sync,
+        P: Fn(&T) -> bool + Sync,
+    {
+        let rule = self.rule;
+        let shared_table = &self.shared_table;
+        let stop_flag = AtomicBool::new(false);
+        let next_index = AtomicUsize::new(0);
+        let results: Vec<Mutex<Option<T>>> = boards.iter().map(|_| Mutex::new(None)).collect();
+
+        std::thread::scope(|scope| {
+            for table in self.tables.iter_mut() {
+                let (stop_flag, next_index, results) = (&stop_flag, &next_index, &results);
+                let (search, stops_on) = (&search, &stops_on);
+                scope.spawn(move || {
+                    let mut searcher = Searcher::new(rule)
+                        .with_table(table)
+                        .with_shared_table(shared_table)
+                        .with_stop_flag(stop_flag);
+                    loop {
+                        let index = next_index.fetch_add(1, AtomicOrdering::Relaxed);
+                        if index >= boards.len() || searcher.is_stopped() {
+                            break;
+                        }
+                        let mut board = boards[index];
+                        let result = search(&mut searcher, &mut board);
+                        if searcher.is_stopped() {
+                            break;
+                        }
+                        let stops = stops_on(&result);
+                        *results[index].lock().unwrap() = Some(result);
+                        if stops {
+                            stop_flag.store(true, AtomicOrdering::Relaxed);
+                        }
+                    }
+                });
+            }
+        });
+
+        results
+            .into_iter()
+            .map(|result| result.into_inner().unwrap())
+            .collect()
+    }
+
+    /// Compares each of `boards` to `value` in parallel.
+    ///
+    /// If `stops_on_less` is `true`, all threads stop once one comparison results in `Less`,
+    /// and results of comparisons not completed are `None`.
+    fn compare_each(
+        &mut self,
+        boards: &[Board],
+        value: BoardValue,
+        player: Color,
+        stops_on_less: bool,
+    ) -> Vec<Option<Ordering>> {
+        self.search_each(
+            boards,
+            |searcher, board| searcher.compare(board, value, player),
+            |cmp| stops_on_less && *cmp == Ordering::Less,
+        )
+    }
+
+    fn compare(&mut self, mut board: Board, value: BoardValue, player: Color) -> Ordering {
+        use Ordering::*;
+        if value == BoardValue::MAX {
+            return Searcher::new(self.rule).compare(&mut board, value, player);
+        }
+
+        let mut next_boards = Vec::new();
+        let flow = try_for_each_unique_next_board(
+            &mut board,
+            player,
+            self.rule,
+            |_, next_board, status| {
+                use NextBoardStatus::*;
+                match status {
+                    Win => return ControlFlow::Break(()),
+                    Lose => (),
+                    Draw => {
+                        if value.is_lose() {
+                            return ControlFlow::Break(());
+                        }
+                    }
+                    Unknown => next_boards.push(*next_board),
+                }
+                ControlFlow::Continue(())
+            },
+        );
+        if flow.is_break() {
+            return Greater;
+        }
+
+        let next_value = value.try_decrement().unwrap();
+        let mut cmp = Less;
+        for next_cmp in self.compare_each(&next_boards, next_value, !player, true) {
+            match next_cmp {
+                Some(Less) | None => return Greater,
+                Some(next_cmp) => cmp = cmp.max(next_cmp.reverse()),
+            }
+        }
+        cmp
+    }
+
+    fn evaluate(&mut self, board: Board, player: Color, search_depth: usize) -> Interval {
+        for depth in 1..=search_depth {
+            let value = BoardValue::from(Some(depth));
+            if matches!(self.compare(board, value, player), Ordering::Equal) {
+                return Interval::new(value, value);
+            }
+        }
+        Interval::beyond(search_depth)
+    }
+
+    fn find_best_actions(
+        &mut self,
+        mut board: Board,
+        player: Color,
+        search_depth: usize,
+    ) -> Vec<Action> {
+        if search_depth == 0 {
+            return Searcher::new(self.rule).find_best_actions(&mut board, player, 0);
+        }
+
+        let value_interval = self.evaluate(board, player, search_depth);
+        let value = value_interval.single().unwrap_or(BoardValue::unknown());
+        if value.is_unknown() && matches!(self.rule.suicide_atk_judge(), Judge::Draw) {
+            return self.find_best_actions_with_draws(board, player, search_depth);
+        }
+
+        let mut actions = Vec::new();
+        let mut candidates = Vec::new();
+        for_each_next_board(
+            &mut board,
+            player,
+            self.rule,
+            |action, next_board, status| {
+                use NextBoardStatus::*;
+                match status {
+                    Win => actions.push(action),
+                    Lose | Draw => (),
+                    Unknown => candidates.push((action, *next_board)),
+                }
+            },
+        );
+        if value == BoardValue::MAX {
+            return actions;
+        }
+
+        // The same conditions as the serial version
+        let next_value = value.try_decrement().unwrap();
+        let (target_value, is_best): (_, fn(Ordering) -> bool) = if next_value.is_unknown() {
+            let left = value_interval.left().try_decrement().unwrap();
+            (left, |cmp| cmp != Ordering::Greater)
+        } else {
+            (next_value, |cmp| cmp == Ordering::Equal)
+        };
+        let next_boards: Vec<Board> = candidates.iter().map(|(_, b)| *b).collect();
+        let results = self.compare_each(&next_boards, target_value, !player, false);
+        for ((action, _), result) in candidates.into_iter().zip(results) {
+            if result.is_some_and(is_best) {
+                actions.push(action);
+            }
+        }
+        actions
+    }
+
+    /// Collects the best actions in the same way as the serial version
+    /// by distributing the evaluation of children with draws to threads.
+    fn find_best_actions_with_draws(
+        &mut self,
+        mut board: Board,
+        player: Color,
+        search_depth: usize,
+    ) -> Vec<Action> {
+        let mut values = Vec::new();
+        let mut candidates = Vec::new();
+        for_each_next_board(
+            &mut board,
+            player,
+            self.rule,
+            |action, next_board, status| {
+                use NextBoardStatus::*;
+                let value = match status {
+                    Win => BoardValue::MAX,
+                    Lose => return,
+                    Draw => BoardValue::draw(1).unwrap(),
+                    Unknown => {
+                        candidates.push((values.len(), *next_board));
+                        BoardValue::unknown()
+                    }
+                };
+                values.push((action, value));
+            },
+        );
+
+        let next_boards: Vec<Board> = candidates.iter().map(|(_, b)| *b).collect();
+        let results = self.search_each(
+            &next_boards,
+            |searcher, next_board| {
+                let mut memo = HashMap::new();
+                searcher.value_with_draws(next_board, !player, search_depth - 1, &mut memo)
+            },
+            |_| false,
+        );
+        for ((i, _), result) in candidates.into_iter().zip(results) {
+            values[i].1 = result.unwrap_or_default().increment();
+        }
+
+        let mut best = BoardValue::MIN;
+        let mut actions = Vec::new();
+        for (action, value) in values {
+            if value > best {
+                best = value;
+                actions.clear();
+            }
+            if value == best {
+                actions.push(action);
+            }
+        }
+        actions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{analysis, BoardBuilder};
+    use std::str::FromStr;
+
+    #[test]
+    fn test_parallel_coincides_with_serial() {
+        let board_strs = [
+            " B; a;TH y;b mM",
+            " By;H  a;A m;  Yb",
+            "bB; H;Y h;  T",
+            "hB A;maYT; Htb;M y",
+            "bB;T YA",
+            "b;B",
+            "    ;btBy;AaMm; H T",
+        ];
+        let rules = [
+            GameRule::new(true),
+            GameRule::new(true).with_suicide_atk_judge(Judge::Draw),
+        ];
+        for (s, rule) in board_strs.into_iter().flat_map(|s| rules.map(|r| (s, r))) {
+            let board = BoardBuilder::from_str(s).unwrap().build().unwrap();
+            for player in [Color::Red, Color::Green] {
+                for num_threads in [1, 3] {
+                    assert_eq!(
+                        evaluate_board_parallel(board, player, 5, rule, num_threads).unwrap(),
+                        analysis::evaluate_board(board, player, 5, rule).unwrap(),
+                    );
+                    assert_eq!(
+                        find_best_actions_parallel(board, player, 5, rule, num_threads).unwrap(),
+                        analysis::find_best_actions(board, player, 5, rule).unwrap(),
+                    );
+                    for num in 1..=5 {
+                        let value = BoardValue::from(Some(num));
+                        assert_eq!(
+                            compare_board_value_parallel(board, value, player, rule, num_threads)
+                                .unwrap(),
+                            analysis::compare_board_value(board, value, player, rule).unwrap(),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_parallel_with_draws() {
+        let board_strs = [
+            "    ;btBy;AaMm; H T",
+            "    ;BMba;AYty;  Th",
+            "myb ; YB ;  T ; H  ",
+            " tm ;hyb ; HB ; MT ",
+            "   b;HMBA;Taym;htY ",
+        ];
+        let rule = GameRule::new(true).with_suicide_atk_judge(Judge::Draw);
+        let depth = 3;
+        let mut num_undetermined = 0;
+        for s in board_strs {
+            let board = BoardBuilder::from_str(s).unwrap().build().unwrap();
+            for player in [Color::Red, Color::Green] {
+                let value = analysis::evaluate_board(board, player, depth, rule).unwrap();
+                if value.single().is_some() {
+                    continue;
+                }
+                num_undetermined += 1;
+                for num_threads in [1, 3] {
+                    assert_eq!(
+                        find_best_actions_parallel(board, player, depth, rule, num_threads)
+                            .unwrap(),
+                        analysis::find_best_actions(board, player, depth, rule).unwrap(),
+                        "{s} {player:?}"
+                    );
+                }
+            }
+        }
+        assert!(num_undetermined > 0);
+    }
+}
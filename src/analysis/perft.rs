@@ -0,0 +1,215 @@
+use crate::{
+    error::{self, PerftErrorKind},
+    game::GameRule,
+    Action, ActionContainer, Board, Color, SurroundedStatus,
+};
+
+/// Counts the number of action sequences of length `depth` starting from `board`.
+///
+/// This is a standard tool to test a move generator ("performance test").
+/// Actions are generated by [`Board::legal_actions`],
+/// where [`Action::Remove`] is contained only if it is accepted by `rule`.
+/// Since no action is performed after the game finished,
+/// sequences reaching a finished board before `depth` actions are not counted.
+/// It returns `1` if `depth` is zero.
+///
+/// # Examples
+/// ```rust
+/// use tokyodoves::{ActionContainer, Board, Color};
+/// use tokyodoves::analysis::perft;
+/// use tokyodoves::game::GameRule;
+///
+/// let board = Board::new();
+/// let rule = GameRule::new(true);
+/// let num_actions = board.legal_actions(Color::Red, true, true, true).len();
+/// assert_eq!(perft(board, Color::Red, 1, rule), num_actions);
+/// ```
+pub fn perft(mut board: Board, player: Color, depth: usize, rule: GameRule) -> usize {
+    perft_raw(&mut board, player, depth, *rule.is_remove_accepted())
+}
+
+fn perft_raw(board: &mut Board, player: Color, depth: usize, is_remove_accepted: bool) -> usize {
+    if depth == 0 {
+        return 1;
+    }
+    if board.surrounded_status() != SurroundedStatus::None {
+        return 0;
+    }
+    let actions = board.legal_actions(player, true, true, is_remove_accepted);
+    if depth == 1 {
+        return actions.len();
+    }
+    let mut count = 0;
+    for action in actions {
+        let token = board.make(action);
+        count += perft_raw(board, !player, depth - 1, is_remove_accepted);
+        board.unmake(token);
+    }
+    count
+}
+
+/// Counts the number of action sequences of length `depth` for each first [`Action`].
+///
+/// The sum of the counts coincides with the result of [`perft`].
+/// The actions are in the same order as [`Board::legal_actions`].
+///
+/// # Examples
+/// ```rust
+/// use tokyodoves::{Board, Color};
+/// use tokyodoves::analysis::{perft, perft_divide};
+/// use tokyodoves::game::GameRule;
+///
+/// let board = Board::new();
+/// let rule = GameRule::new(true);
+/// let divided = perft_divide(board, Color::Red, 3, rule);
+/// let total: usize = divided.iter().map(|(_, count)| count).sum();
+/// assert_eq!(total, perft(board, Color::Red, 3, rule));
+/// ```
+pub fn perft_divide(
+    mut board: Board,
+    player: Color,
+    depth: usize,
+    rule: GameRule,
+) -> Vec<(Action, usize)> {
+    if depth == 0 || board.surrounded_status() != SurroundedStatus::None {
+        return Vec::new();
+    }
+    let is_remove_accepted = *rule.is_remove_accepted();
+    let actions = board.legal_actions(player, true, true, is_remove_accepted);
+    let mut divided = Vec::new();
+    for action in actions {
+        let token = board.make(action);
+        divided.push((
+            action,
+            perft_raw(&mut board, !player, depth - 1, is_remove_accepted),
+        ));
+        board.unmake(token);
+    }
+    divided
+}
+
+/// Counts the number of action sequences like [`perft`]
+/// checking consistency of action generation at every board.
+///
+/// For each action generated by [`Board::legal_actions`], it checks that
+/// - [`Board::check_action`] accepts the action,
+/// - [`Board::perform`] results in the same board as [`Board::perform_unchecked`]
+///     and [`Board::make`],
+/// - [`Board::unmake`] restores the original board, and
+/// - [`Board::legal_actions_bwd`] on the resulting board contains an action
+///     leading back to the original board (up to translation).
+///
+/// # Errors
+/// Returns `Err` with [`PerftErrorKind`] if any of the checks above fails.
+///
+/// # Examples
+/// ```rust
+/// use tokyodoves::{Board, Color};
+/// use tokyodoves::analysis::{perft, perft_checked};
+/// use tokyodoves::game::GameRule;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let board = Board::new();
+/// let rule = GameRule::new(true);
+/// assert_eq!(perft_checked(board, Color::Red, 2, rule)?, perft(board, Color::Red, 2, rule));
+/// # Ok(())
+/// # }
+/// ```
+pub fn perft_checked(
+    board: Board,
+    player: Color,
+    depth: usize,
+    rule: GameRule,
+) -> Result<usize, error::Error> {
+    if depth == 0 {
+        return Ok(1);
+    }
+    if board.surrounded_status() != SurroundedStatus::None {
+        return Ok(0);
+    }
+    let is_remove_accepted = *rule.is_remove_accepted();
+    let mut count = 0;
+    for action in board.legal_actions(player, true, true, is_remove_accepted) {
+        board
+            .check_action(action)
+            .map_err(|_| PerftErrorKind::CheckActionFailed(Box::new(board), action))?;
+        let mut next_board = board;
+        let token = next_board.make(action);
+        let performed = board
+            .perform_copied(action)
+            .map_err(|_| PerftErrorKind::PerformFailed(Box::new(board), action))?;
+        let mut restored = next_board;
+        restored.unmake(token);
+        if performed != next_board
+            || performed != board.perform_unchecked_copied(action)
+            || restored != board
+        {
+            return Err(PerftErrorKind::PerformMismatch(Box::new(board), action).into());
+        }
+
+        // Putting in backward direction corresponds to removing in forward direction.
+        // Boards are compared up to translation
+        // because removing a dove does not move the field back.
+        let key = board.to_translation_invariant_u64();
+        let has_inverse = next_board
+            .legal_actions_bwd(player, is_remove_accepted, true, true)
+            .into_iter()
+            .any(|inverse| {
+                let token = next_board.make(inverse);
+                let found = next_board.to_translation_invariant_u64() == key;
+                next_board.unmake(token);
+                found
+            });
+        if !has_inverse {
+            return Err(PerftErrorKind::InverseNotFound(Box::new(board), action).into());
+        }
+
+        count += perft_checked(next_board, !player, depth - 1, rule)?;
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BoardBuilder;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_perft_from_initial_board() {
+        let board = Board::new();
+        for (rule, expected) in [
+            (GameRule::new(true), [1, 29, 827, 27481]),
+            (GameRule::new(false), [1, 29, 827, 26766]),
+        ] {
+            for (depth, expected) in expected.into_iter().enumerate() {
+                assert_eq!(perft(board, Color::Red, depth, rule), expected);
+            }
+            assert_eq!(
+                perft_checked(board, Color::Red, 3, rule).unwrap(),
+                expected[3]
+            );
+        }
+    }
+
+    #[test]
+    fn test_perft_consistency() {
+        let board_strs = [
+            " B; a;TH y;b mM",
+            " By;H  a;A m;  Yb",
+            "bB; H;Y h;  T",
+            "hB A;maYT; Htb;M y",
+            "bB;T YA",
+        ];
+        for (s, remove) in board_strs.into_iter().flat_map(|s| [(s, true), (s, false)]) {
+            let board = BoardBuilder::from_str(s).unwrap().build().unwrap();
+            let rule = GameRule::new(remove);
+            for player in [Color::Red, Color::Green] {
+                let count = perft(board, player, 2, rule);
+                assert_eq!(perft_checked(board, player, 2, rule).unwrap(), count);
+                let divided = perft_divide(board, player, 2, rule);
+                assert_eq!(divided.iter().map(|(_, n)| n).sum::<usize>(), count);
+            }
+        }
+    }
+}
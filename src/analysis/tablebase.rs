@@ -0,0 +1,525 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::ops::ControlFlow;
+use std::path::Path;
+
+use crate::{
+    analysis::{
+        board_value::{
+            for_each_next_board, try_for_each_next_board, validate_args, NextBoardStatus, Searcher,
+        },
+        BoardValue, Interval,
+    },
+    collections::BoardSet,
+    error,
+    game::{GameRule, Judge},
+    Action, Board, BoardBuilder, Color, SurroundedStatus,
+};
+
+/// The name of the file describing a saved [`Tablebase`]
+const INFO_FILE_NAME: &str = "tablebase.txt";
+
+/// An endgame tablebase created by retrograde analysis.
+///
+/// It contains boards with at most [`max_doves`](`Tablebase::max_doves`) doves on the field
+/// whose values are proved to be `Win(n)` or `Lose(n)`.
+/// The tablebase is generated by stepping backwards
+/// with [`Board::legal_actions_bwd`] from boards just before the end of the game.
+///
+/// Note that a dove can always be put from the hand,
+/// so boards with more doves may appear in the games starting from the boards in the tablebase.
+/// Such games are not taken into account, which means that
+/// `Win(n)` in the tablebase guarantees that the player wins within n turns
+/// (the true value may be `Win(m)` for some `m < n`),
+/// and `Lose(n)` guarantees that the player loses within n turns
+/// (the true value may be `Lose(m)` for some `m < n`).
+/// Search functions such as [`evaluate_board_with_tablebase`] take this into account.
+///
+/// Each layer, i.e., the set of boards with the same value, is stored as a [`BoardSet`],
+/// where boards are normalized by [`Board::to_invariant_u64`]
+/// so that the next player is red.
+///
+/// # Examples
+/// ```rust
+/// use tokyodoves::{Board, Color};
+/// use tokyodoves::analysis::Tablebase;
+/// use tokyodoves::game::GameRule;
+///
+/// let rule = GameRule::new(true);
+/// let tablebase = Tablebase::generate(4, rule);
+/// assert_eq!(tablebase.max_doves(), 4);
+/// for (n, layer) in tablebase.layers().iter().enumerate() {
+///     println!("{} boards with value {:?}", layer.len(), tablebase.value_of_layer(n + 1));
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Tablebase {
+    max_doves: usize,
+    rule: GameRule,
+    /// `layers[n - 1]` contains boards with value `Win(n)` or `Lose(n)`
+    layers: Vec<BoardSet>,
+}
+
+impl Tablebase {
+    /// Generates a tablebase of boards with at most `max_doves` doves on the field.
+    ///
+    /// Only `is_remove_accepted` and `suicide_atk_judge` of `rule` are taken into account.
+    ///
+    /// Note that the number of boards grows rapidly with `max_doves`.
+    /// For example, about 2.8 million boards are stored when `max_doves` is 6,
+    /// and it takes several minutes to generate them.
+    pub fn generate(max_doves: usize, rule: GameRule) -> Self {
+        let generator = Generator::new(max_doves, rule);
+        let initial = Board::new().to_invariant_u64(Color::Red);
+        let universe = generator.enumerate(initial);
+        generator.run(universe)
+    }
+
+    /// Returns the maximum number of doves on the field of boards in the tablebase.
+    pub fn max_doves(&self) -> usize {
+        self.max_doves
+    }
+
+    /// Returns a reference to the rule used to generate the tablebase.
+    pub fn rule(&self) -> &GameRule {
+        &self.rule
+    }
+
+    /// Returns the layers of the tablebase.
+    ///
+    /// The n-th layer (`layers()[n - 1]`) contains boards
+    /// whose value is given by [`value_of_layer(n)`](`Tablebase::value_of_layer`).
+    pub fn layers(&self) -> &[BoardSet] {
+        &self.layers
+    }
+
+    /// Returns the value of boards in the `n`-th layer,
+    /// which is `Win(n)` for odd `n` and `Lose(n)` for even `n`.
+    ///
+    /// It returns `None` if `n` is zero.
+    pub fn value_of_layer(&self, n: usize) -> Option<BoardValue> {
+        BoardValue::win(n).or(BoardValue::lose(n))
+    }
+
+    /// Returns the number of boards in the tablebase.
+    pub fn len(&self) -> usize {
+        self.layers.iter().map(BoardSet::len).sum()
+    }
+
+    /// Returns `true` if the tablebase contains no boards.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the value of `board` stored in the tablebase.
+    ///
+    /// It returns `None` if `board` is not in the tablebase.
+    /// See the documentation of [`Tablebase`] for the meaning of the returned value.
+    pub fn get(&self, board: &Board, player: Color) -> Option<BoardValue> {
+        if board.count_doves_on_field() > self.max_doves {
+            return None;
+        }
+        let normalized = board.normalize(player);
+        self.layers
+            .iter()
+            .position(|layer| layer.contains(&normalized))
+            .and_then(|i| self.value_of_layer(i + 1))
+    }
+
+    /// Saves the tablebase into the directory `dir`.
+    ///
+    /// Each layer is saved by [`BoardSet::save`] as a file named "layer{n}.bin"
+    /// together with a text file describing the tablebase.
+    /// The directory is created if it does not exist.
+    pub fn save(&self, dir: impl AsRef<Path>) -> std::io::Result<()> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        let mut info = BufWriter::new(File::create(dir.join(INFO_FILE_NAME))?);
+        writeln!(info, "max_doves: {}", self.max_doves)?;
+        writeln!(info, "remove: {}", self.rule.is_remove_accepted())?;
+        writeln!(info, "judge: {:?}", self.rule.suicide_atk_judge())?;
+        writeln!(info, "layers: {}", self.layers.len())?;
+        for (i, layer) in self.layers.iter().enumerate() {
+            layer.save(File::create(dir.join(layer_file_name(i + 1)))?)?;
+        }
+        info.flush()?;
+        Ok(())
+    }
+
+    /// Loads a tablebase saved by [`save`](`Tablebase::save`) from the directory `dir`.
+    pub fn load(dir: impl AsRef<Path>) -> std::io::Result<Self> {
+        fn invalid(line: &str) -> std::io::Error {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("invalid line in {INFO_FILE_NAME}: {line:?}"),
+            )
+        }
+
+        let dir = dir.as_ref();
+        let (mut max_doves, mut num_layers) = (None, None);
+        let mut rule = GameRule::new(true);
+        for line in BufReader::new(File::open(dir.join(INFO_FILE_NAME))?).lines() {
+            let line = line?;
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim() {
+                "max_doves" => max_doves = Some(value.parse().map_err(|_| invalid(&line))?),
+                "layers" => num_layers = Some(value.parse().map_err(|_| invalid(&line))?),
+                "remove" => {
+                    let is_remove_accepted = value.parse().map_err(|_| invalid(&line))?;
+                    rule = rule.with_is_remove_accepted(is_remove_accepted);
+                }
+                "judge" => {
+                    let judge = [Judge::LastWins, Judge::NextWins, Judge::Draw]
+                        .into_iter()
+                        .find(|j| format!("{j:?}") == value)
+                        .ok_or_else(|| invalid(&line))?;
+                    rule = rule.with_suicide_atk_judge(judge);
+                }
+                _ => return Err(invalid(&line)),
+            }
+        }
+        let (Some(max_doves), Some(num_layers)) = (max_doves, num_layers) else {
+            return Err(invalid("max_doves or layers is missing"));
+        };
+
+        let mut layers = Vec::with_capacity(num_layers);
+        for n in 1..=num_layers {
+            layers.push(BoardSet::new_from_file(dir.join(layer_file_name(n)))?);
+        }
+        Ok(Self {
+            max_doves,
+            rule,
+            layers,
+        })
+    }
+
+    /// Returns `true` if the tablebase can be used in searches under `rule`.
+    pub(crate) fn is_compatible_with(&self, rule: &GameRule) -> bool {
+        self.rule.is_remove_accepted() == rule.is_remove_accepted()
+            && self.rule.suicide_atk_judge() == rule.suicide_atk_judge()
+    }
+}
+
+fn layer_file_name(n: usize) -> String {
+    format!("layer{n}.bin")
+}
+
+/// A context of retrograde analysis
+struct Generator {
+    max_doves: usize,
+    rule: GameRule,
+    /// `n` of `Win(n)` or `Lose(n)` of labeled boards
+    labels: HashMap<u64, usize>,
+}
+
+impl Generator {
+    fn new(max_doves: usize, rule: GameRule) -> Self {
+        Self {
+            max_doves,
+            rule,
+            labels: HashMap::new(),
+        }
+    }
+
+    /// Labels boards by retrograde analysis starting from `universe`.
+    fn run(mut self, universe: HashSet<u64>) -> Tablebase {
+        // Boards where red wins by one action
+        let mut layer: Vec<u64> = universe
+            .into_iter()
+            .filter(|&key| {
+                let mut board = Self::board(key);
+                try_for_each_next_board(&mut board, Color::Red, self.rule, |_, _, status| {
+                    match status {
+                        NextBoardStatus::Win => ControlFlow::Break(()),
+                        _ => ControlFlow::Continue(()),
+                    }
+                })
+                .is_break()
+            })
+            .collect();
+        let mut layers = Vec::new();
+        let mut n = 1;
+        while !layer.is_empty() {
+            for key in layer.iter() {
+                self.labels.insert(*key, n);
+            }
+            layers.push(layer.iter().map(|&key| Self::board(key)).collect());
+
+            // Boards one action before the current layer are candidates of the next layer
+            n += 1;
+            let candidates: HashSet<u64> = layer
+                .iter()
+                .flat_map(|&key| self.predecessors(key))
+                .filter(|key| !self.labels.contains_key(key))
+                .collect();
+            layer = candidates
+                .into_iter()
+                .filter(|&key| self.is_labeled_as(key, n))
+                .collect();
+        }
+        Tablebase {
+            max_doves: self.max_doves,
+            rule: self.rule,
+            layers,
+        }
+    }
+
+    fn board(key: u64) -> Board {
+        BoardBuilder::from_u64(key).build_unchecked()
+    }
+
+    /// Enumerates boards (red to move) connected to `initial`
+    /// by actions in both directions without exceeding `max_doves`.
+    fn enumerate(&self, initial: u64) -> HashSet<u64> {
+        let mut visited = HashSet::from([initial]);
+        let mut stack = vec![initial];
+        while let Some(key) = stack.pop() {
+            let successors = self.successors(key).into_iter().flatten();
+            for next_key in successors.chain(self.predecessors(key)) {
+                if visited.insert(next_key) {
+                    stack.push(next_key);
+                }
+            }
+        }
+        visited
+    }
+
+    /// Returns keys of boards after actions of red,
+    /// where `None` means that the board is out of the tablebase or the game finished.
+    fn successors(&self, key: u64) -> Vec<Option<u64>> {
+        let mut successors = Vec::new();
+        for_each_next_board(
+            &mut Self::board(key),
+            Color::Red,
+            self.rule,
+            |_, next_board, status| {
+                if status != NextBoardStatus::Lose {
+                    successors.push(
+                        (status == NextBoardStatus::Unknown
+                            && next_board.count_doves_on_field() <= self.max_doves)
+                            .then(|| next_board.to_invariant_u64(Color::Green)),
+                    );
+                }
+            },
+        );
+        successors
+    }
+
+    /// Returns keys of boards before actions of green.
+    fn predecessors(&self, key: u64) -> Vec<u64> {
+        let mut board = Self::board(key);
+        let is_remove_accepted = *self.rule.is_remove_accepted();
+        // Putting in backward direction corresponds to removing in forward direction
+        let actions = board.legal_actions_bwd(Color::Green, is_remove_accepted, true, true);
+        let mut keys = Vec::new();
+        for action in actions {
+            let token = board.make(action);
+            if board.surrounded_status() == SurroundedStatus::None
+                && board.count_doves_on_field() <= self.max_doves
+            {
+                keys.push(board.to_invariant_u64(Color::Green));
+            }
+            board.unmake(token);
+        }
+        keys
+    }
+
+    /// Checks whether the board should be labeled in the `n`-th layer.
+    fn is_labeled_as(&self, key: u64, n: usize) -> bool {
+        let successors = self.successors(key);
+        let mut labels = successors
+            .iter()
+            .map(|next_key| next_key.and_then(|k| self.labels.get(&k).copied()));
+        if n % 2 == 1 {
+            // Win(n) if an action leads to Lose(n - 1)
+            labels.any(|label| label == Some(n - 1))
+        } else {
+            // Lose(n) if all actions lead to Win
+            !successors.is_empty() && labels.all(|label| label.is_some_and(|m| m % 2 == 1))
+        }
+    }
+}
+
+impl<'a> Searcher<'a> {
+    /// Compares the value of `board` to `value` using the tablebase if possible.
+    ///
+    /// It returns `None` if the tablebase does not determine the result.
+    pub(crate) fn compare_by_tablebase(
+        &mut self,
+        tablebase: &Tablebase,
+        board: &mut Board,
+        value: BoardValue,
+        player: Color,
+    ) -> Option<std::cmp::Ordering> {
+        use std::cmp::Ordering::*;
+        let bound = tablebase.get(board, player)?;
+        // `bound` is Win(n) (the value >= Win(n)) or Lose(n) (the value <= Lose(n))
+        let is_win = bound.is_win();
+        match (value.partial_cmp(&bound)?, is_win) {
+            (Less, true) => Some(Greater),
+            (Greater, false) => Some(Less),
+            (Equal, _) => {
+                // Whether the value is strictly better (or worse) than `bound`
+                let n = bound.unwrap();
+                if n <= 2 {
+                    return Some(Equal);
+                }
+                let cmp = self.compare(board, BoardValue::from(Some(n - 2)), player);
+                Some(match (is_win, cmp) {
+                    (true, Less) | (false, Greater) => Equal,
+                    (true, _) => Greater,
+                    (false, _) => Less,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Calculates a possible range of [`BoardValue`] of specified [`Board`]
+/// with the help of [`Tablebase`].
+///
+/// The result is at least as narrow as that of [`evaluate_board`](`super::evaluate_board`).
+/// Values of boards in the tablebase are used as bounds during the search,
+/// so that the value of `board` may be determined even if it is beyond `search_depth`.
+///
+/// # Errors
+/// Returns `Err` only when `board` is already finished (at least one boss is surrounded).
+/// If the tablebase was generated under a rule different from `rule`,
+/// this function falls back to [`evaluate_board`](`super::evaluate_board`).
+///
+/// # Examples
+/// ```rust
+/// use std::str::FromStr;
+/// use tokyodoves::{BoardBuilder, Color};
+/// use tokyodoves::analysis::{evaluate_board_with_tablebase, Tablebase};
+/// use tokyodoves::game::GameRule;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let rule = GameRule::new(true);
+/// let tablebase = Tablebase::generate(4, rule);
+/// let board = BoardBuilder::from_str("Bb;a")?.build()?;
+/// let value = evaluate_board_with_tablebase(board, Color::Red, 1, rule, &tablebase)?;
+/// println!("{value}");
+/// # Ok(())
+/// # }
+/// ```
+pub fn evaluate_board_with_tablebase(
+    mut board: Board,
+    player: Color,
+    search_depth: usize,
+    rule: GameRule,
+    tablebase: &Tablebase,
+) -> Result<Interval, error::Error> {
+    validate_args(board, BoardValue::MAX)?;
+    Ok(Searcher::new(rule)
+        .with_tablebase(tablebase)
+        .evaluate(&mut board, player, search_depth))
+}
+
+/// Collects the best [`Action`]s by [`BoardValue`] with the help of [`Tablebase`].
+///
+/// It returns the same actions as [`find_best_actions`](`super::find_best_actions`)
+/// except that the value of `board` is calculated by [`evaluate_board_with_tablebase`].
+///
+/// # Errors
+/// Returns `Err` in the same cases as [`evaluate_board_with_tablebase`].
+pub fn find_best_actions_with_tablebase(
+    mut board: Board,
+    player: Color,
+    search_depth: usize,
+    rule: GameRule,
+    tablebase: &Tablebase,
+) -> Result<Vec<Action>, error::Error> {
+    validate_args(board, BoardValue::MAX)?;
+    Ok(Searcher::new(rule)
+        .with_tablebase(tablebase)
+        .find_best_actions(&mut board, player, search_depth))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis;
+    use std::cmp::Ordering;
+    use std::str::FromStr;
+
+    /// Creates a tablebase from boards within `depth` turns from `board_str`
+    /// instead of enumerating all boards, which takes too long
+    fn create_tablebase(board_str: &str, depth: usize, rule: GameRule) -> Tablebase {
+        let generator = Generator::new(6, rule);
+        let board = BoardBuilder::from_str(board_str).unwrap().build().unwrap();
+        let mut layer = vec![board.to_invariant_u64(Color::Red)];
+        let mut universe: HashSet<u64> = layer.iter().copied().collect();
+        for _ in 0..depth {
+            layer = layer
+                .iter()
+                .flat_map(|&key| generator.successors(key))
+                .flatten()
+                .filter(|&key| universe.insert(key))
+                .collect();
+        }
+        generator.run(universe)
+    }
+
+    #[test]
+    fn test_tablebase_is_consistent_with_search() {
+        let rule = GameRule::new(true);
+        for (board_str, num) in [("Ba  ; h  ;y b ;   M", 4), ("M   ; Bb ;A Y ;   T", 5)] {
+            let tablebase = create_tablebase(board_str, num - 1, rule);
+            let board = BoardBuilder::from_str(board_str).unwrap().build().unwrap();
+            assert_eq!(
+                tablebase.get(&board, Color::Red),
+                tablebase.value_of_layer(num)
+            );
+            for (i, layer) in tablebase.layers().iter().enumerate() {
+                let value = tablebase.value_of_layer(i + 1).unwrap();
+                for board in layer.iter().take(5) {
+                    assert_eq!(tablebase.get(&board, Color::Red), Some(value));
+                    let cmp =
+                        analysis::compare_board_value(board, value, Color::Red, rule).unwrap();
+                    if value.is_win() {
+                        assert_ne!(cmp, Ordering::Less);
+                    } else {
+                        assert_ne!(cmp, Ordering::Greater);
+                    }
+                    assert_eq!(
+                        evaluate_board_with_tablebase(board, Color::Red, i + 1, rule, &tablebase)
+                            .unwrap(),
+                        analysis::evaluate_board(board, Color::Red, i + 1, rule).unwrap(),
+                    );
+                    // The tablebase may determine values beyond the search depth
+                    let interval =
+                        analysis::evaluate_board(board, Color::Green, i + 1, rule).unwrap();
+                    let narrowed =
+                        evaluate_board_with_tablebase(board, Color::Green, i + 1, rule, &tablebase)
+                            .unwrap();
+                    assert!(interval.contains(narrowed.left()));
+                    assert!(interval.contains(narrowed.right()));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_tablebase_save_and_load() {
+        let rule = GameRule::new(false).with_suicide_atk_judge(Judge::LastWins);
+        let tablebase = create_tablebase("Ba  ; h  ;y b ;   M", 3, rule);
+        let dir = std::env::temp_dir().join(format!("tokyodoves_tablebase_{}", std::process::id()));
+        tablebase.save(&dir).unwrap();
+        let loaded = Tablebase::load(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(loaded.max_doves(), tablebase.max_doves());
+        assert!(loaded.is_compatible_with(&rule));
+        assert!(!loaded.is_compatible_with(&GameRule::new(true)));
+        assert_eq!(loaded.len(), tablebase.len());
+        for (layer, loaded_layer) in tablebase.layers().iter().zip(loaded.layers()) {
+            assert!(layer.iter().all(|board| loaded_layer.contains(&board)));
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use crate::{
+    analysis::BoardValue,
+    game::{GameRule, Judge},
+    Board, Color,
+};
+
+/// A key identifying a board and the next player in [`TranspositionTable`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct TableKey {
+    /// The index in the table, which may collide with those of other boards
+    hash: u64,
+    /// The exact representation of the board to detect collisions
+    board: u64,
+}
+
+/// Entry of [`TranspositionTable`]
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    board: u64,
+    bounds: Bounds,
+}
+
+/// Proven bounds of the value of one board
+#[derive(Debug, Clone, Copy, Default)]
+struct Bounds {
+    /// The exact value if it was proven
+    exact: Option<BoardValue>,
+    /// The greatest value known to be less than the value of the board
+    greater_than: Option<BoardValue>,
+    /// The least value known to be greater than the value of the board
+    less_than: Option<BoardValue>,
+}
+
+impl Bounds {
+    fn compare(&self, value: BoardValue) -> Option<Ordering> {
+        if let Some(exact) = self.exact {
+            return exact.partial_cmp(&value);
+        }
+        if matches!(self.greater_than, Some(g) if value <= g) {
+            return Some(Ordering::Greater);
+        }
+        if matches!(self.less_than, Some(l) if value >= l) {
+            return Some(Ordering::Less);
+        }
+        None
+    }
+
+    fn update(&mut self, value: BoardValue, cmp: Ordering) {
+        use Ordering::*;
+        match cmp {
+            Equal => self.exact = Some(value),
+            Greater => {
+                if !matches!(self.greater_than, Some(g) if g >= value) {
+                    self.greater_than = Some(value);
+                }
+            }
+            Less => {
+                if !matches!(self.less_than, Some(l) if l <= value) {
+                    self.less_than = Some(value);
+                }
+            }
+        }
+    }
+}
+
+/// A table to memorize results of comparisons between [`BoardValue`]s
+/// and values of [`Board`]s during searches.
+///
+/// Boards are indexed by the return value of the
+/// [`invariant_zobrist_hash`](`Board::invariant_zobrist_hash`) method,
+/// which is updated incrementally during searches,
+/// so that boards coinciding with each other under symmetry
+/// share proven bounds of their values.
+/// Each entry also holds the return value of [`to_invariant_u64`](`Board::to_invariant_u64`)
+/// so that boards whose hashes collide never share bounds.
+///
+/// Pass the table to functions such as [`evaluate_board_with_table`](`super::evaluate_board_with_table`)
+/// to reuse results across searches.
+/// Since values of boards depend on [`GameRule`],
+/// the table is automatically cleared when it is used with a rule
+/// different from the previous one.
+///
+/// # Examples
+/// ```rust
+/// use std::str::FromStr;
+/// use tokyodoves::{BoardBuilder, Color};
+/// use tokyodoves::analysis::{evaluate_board_with_table, BoardValue, TranspositionTable};
+/// use tokyodoves::game::GameRule;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let board = BoardBuilder::from_str("bB;T YA")?.build()?;
+/// let rule = GameRule::new(true);
+/// let mut table = TranspositionTable::new();
+/// let value = evaluate_board_with_table(board, Color::Red, 5, rule, &mut table)?;
+/// assert_eq!(value.single(), BoardValue::win(5));
+/// assert!(!table.is_empty());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TranspositionTable {
+    entries: HashMap<u64, Entry>,
+    rule: Option<(bool, Judge)>,
+}
+
+impl TranspositionTable {
+    /// Creates an empty table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty table with at least the specified capacity.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::with_capacity(capacity),
+            rule: None,
+        }
+    }
+
+    /// Returns the number of boards in the table.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the table contains no boards.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Clears the table.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.rule = None;
+    }
+
+    /// Clears the table if it has been used with a rule different from `rule`.
+    pub(crate) fn adapt_to(&mut self, rule: &GameRule) {
+        let rule_key = (*rule.is_remove_accepted(), *rule.suicide_atk_judge());
+        if self.rule != Some(rule_key) {
+            self.entries.clear();
+            self.rule = Some(rule_key);
+        }
+    }
+
+    pub(crate) fn key(board: &Board, player: Color) -> TableKey {
+        TableKey {
+            hash: board.invariant_zobrist_hash(player),
+            board: board.to_invariant_u64(player),
+        }
+    }
+
+    /// Returns the result of comparison between the value of the board
+    /// and `value` if it can be derived from proven bounds.
+    pub(crate) fn probe(&self, key: TableKey, value: BoardValue) -> Option<Ordering> {
+        let entry = self.entries.get(&key.hash)?;
+        if entry.board != key.board {
+            return None;
+        }
+        entry.bounds.compare(value)
+    }
+
+    /// Memorizes the result of comparison,
+    /// replacing the entry of another board with the same hash if any.
+    pub(crate) fn store(&mut self, key: TableKey, value: BoardValue, cmp: Ordering) {
+        let entry = self.entries.entry(key.hash).or_insert(Entry {
+            board: key.board,
+            bounds: Bounds::default(),
+        });
+        if entry.board != key.board {
+            *entry = Entry {
+                board: key.board,
+                bounds: Bounds::default(),
+            };
+        }
+        entry.bounds.update(value, cmp);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_collision() {
+        let key = TableKey { hash: 0, board: 1 };
+        let colliding = TableKey { hash: 0, board: 2 };
+        let value = BoardValue::win(3).unwrap();
+        let mut table = TranspositionTable::new();
+        table.store(key, value, Ordering::Equal);
+        assert_eq!(table.probe(key, value), Some(Ordering::Equal));
+        assert_eq!(table.probe(colliding, value), None);
+
+        table.store(colliding, value, Ordering::Less);
+        assert_eq!(table.probe(colliding, value), Some(Ordering::Less));
+        assert_eq!(table.probe(key, value), None);
+        assert_eq!(table.len(), 1);
+    }
+}
@@ -0,0 +1,230 @@
+use crate::{
+    analysis::{
+        board_value::{validate_args, Searcher},
+        BoardValue, Interval, TranspositionTable,
+    },
+    error,
+    game::GameRule,
+    Action, Board, Color, SurroundedStatus,
+};
+
+/// A value of a board together with one principal variation,
+/// i.e., a sequence of [`Action`]s of both players under optimal play.
+///
+/// It is returned by [`evaluate_board_with_pv`].
+/// The player who wins plays one of the fastest actions to win
+/// and the player who loses plays one of the slowest actions to lose,
+/// so the length of the variation coincides with the number of the value
+/// (e.g. 5 actions for `Win(5)`).
+/// The variation is empty if the value is not determined.
+///
+/// With the [`Display`](`std::fmt::Display`) trait,
+/// it is written like `win in 5: +AS1, -aN2, ...`,
+/// where actions are written in SSN.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrincipalVariation {
+    board: Board,
+    player: Color,
+    interval: Interval,
+    actions: Vec<Action>,
+}
+
+impl std::fmt::Display for PrincipalVariation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Some(value) = self.value() else {
+            return write!(f, "unknown: {}", self.interval);
+        };
+        if value.is_win() {
+            write!(f, "win in {}", value.unwrap())?;
+        } else if value.is_lose() {
+            write!(f, "lose in {}", value.unwrap())?;
+        } else {
+            write!(f, "{value}")?;
+        }
+        let ssns = self.to_ssns().map_err(|_| std::fmt::Error)?;
+        write!(f, ": {}", ssns.join(", "))
+    }
+}
+
+impl PrincipalVariation {
+    /// Returns [`Board`] at the beginning of the variation.
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Returns a reference to the player who performs the first action.
+    pub fn player(&self) -> &Color {
+        &self.player
+    }
+
+    /// Returns a reference to the possible range of the value of the board.
+    pub fn interval(&self) -> &Interval {
+        &self.interval
+    }
+
+    /// Returns the value of the board if it is determined.
+    pub fn value(&self) -> Option<BoardValue> {
+        self.interval.single()
+    }
+
+    /// Returns a reference to the actions in the variation.
+    pub fn actions(&self) -> &[Action] {
+        &self.actions
+    }
+
+    /// Returns the actions in the variation written in SSN.
+    ///
+    /// # Errors
+    /// Returns `Err` if some action cannot be converted into SSN,
+    /// which does not happen for variations returned by [`evaluate_board_with_pv`].
+    pub fn to_ssns(&self) -> Result<Vec<String>, error::Error> {
+        let mut board = self.board;
+        let mut ssns = Vec::new();
+        for action in self.actions.iter() {
+            ssns.push(action.try_into_ssn(&board)?);
+            board.perform_unchecked(*action);
+        }
+        Ok(ssns)
+    }
+
+    /// Returns [`Board`] at the end of the variation.
+    pub fn last_board(&self) -> Board {
+        let mut board = self.board;
+        for action in self.actions.iter() {
+            board.perform_unchecked(*action);
+        }
+        board
+    }
+}
+
+/// Calculates a possible range of [`BoardValue`] of specified [`Board`]
+/// together with one principal variation.
+///
+/// The range is the same as the one returned by [`evaluate_board`](`super::evaluate_board`).
+/// If the value is determined, the variation leads to the end of the game
+/// under optimal play of both players.
+/// See [`PrincipalVariation`] for details.
+///
+/// # Errors
+/// Returns `Err` only when the argument is invalid. Specifically,
+/// the following cases are invalid:
+/// - `board` is already finished (at least one boss is surrounded)
+///
+/// # Examples
+/// ```rust
+/// use std::str::FromStr;
+/// use tokyodoves::{BoardBuilder, Color};
+/// use tokyodoves::analysis::{evaluate_board_with_pv, BoardValue};
+/// use tokyodoves::game::GameRule;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let board = BoardBuilder::from_str("bB;T YA")?.build()?;
+/// let rule = GameRule::new(true);
+/// let pv = evaluate_board_with_pv(board, Color::Red, 5, rule)?;
+/// assert_eq!(pv.value(), BoardValue::win(5));
+/// assert_eq!(pv.actions().len(), 5);
+/// assert!(pv.to_string().starts_with("win in 5: "));
+/// # Ok(())
+/// # }
+/// ```
+pub fn evaluate_board_with_pv(
+    mut board: Board,
+    player: Color,
+    search_depth: usize,
+    rule: GameRule,
+) -> Result<PrincipalVariation, error::Error> {
+    validate_args(board, BoardValue::MAX)?;
+    let mut table = TranspositionTable::new();
+    let mut searcher = Searcher::new(rule).with_table(&mut table);
+    let interval = searcher.evaluate(&mut board, player, search_depth);
+
+    let mut actions = Vec::new();
+    if let Some(num) = interval.single().and_then(|value| value.try_unwrap()) {
+        let (mut next_board, mut next_player) = (board, player);
+        // The value of the board after k actions is determined by searching num - k turns
+        for depth in (1..=num).rev() {
+            let Some(action) = searcher
+                .find_best_actions(&mut next_board, next_player, depth)
+                .first()
+                .copied()
+            else {
+                break;
+            };
+            actions.push(action);
+            next_board.perform_unchecked(action);
+            next_player = !next_player;
+            if next_board.surrounded_status() != SurroundedStatus::None {
+                break;
+            }
+        }
+    }
+
+    Ok(PrincipalVariation {
+        board,
+        player,
+        interval,
+        actions,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        analysis::evaluate_board,
+        game::{Game, GameStatus, Judge},
+        BoardBuilder,
+    };
+    use std::str::FromStr;
+
+    #[test]
+    fn test_principal_variation() {
+        let rule = GameRule::new(true).with_suicide_atk_judge(Judge::NextWins);
+        for (s, num) in [
+            (" B; a;TH y;b mM", 5),
+            (" By;H  a;A m;  Yb", 3),
+            ("bB; H;Y h;  T", 3),
+            ("bB;T YA", 5),
+        ] {
+            let board = BoardBuilder::from_str(s).unwrap().build().unwrap();
+            let pv = evaluate_board_with_pv(board, Color::Red, num, rule).unwrap();
+            assert_eq!(pv.value(), BoardValue::win(num));
+            assert_eq!(pv.actions().len(), num);
+            assert_eq!(pv.to_ssns().unwrap().len(), num);
+
+            // Every position in the variation has the expected value
+            let rule = rule.with_initial_board(board).unwrap();
+            let mut game = Game::new_with_rule(rule);
+            for (ply, action) in pv.actions().iter().enumerate() {
+                let remaining = num - ply;
+                let interval =
+                    evaluate_board(*game.board(), *game.next_player(), remaining, rule).unwrap();
+                let expected = if ply % 2 == 0 {
+                    BoardValue::win(remaining)
+                } else {
+                    BoardValue::lose(remaining)
+                };
+                assert_eq!(interval.single(), expected);
+                game.perform(*action).unwrap();
+            }
+            assert_eq!(*game.status(), GameStatus::Win(Color::Red));
+            assert_eq!(pv.last_board(), *game.board());
+
+            // The variation of the loser
+            let next_board = board.perform_unchecked_copied(pv.actions()[0]);
+            let pv = evaluate_board_with_pv(next_board, Color::Green, num - 1, rule).unwrap();
+            assert_eq!(pv.value(), BoardValue::lose(num - 1));
+            assert_eq!(pv.actions().len(), num - 1);
+            assert!(pv.to_string().starts_with(&format!("lose in {}: ", num - 1)));
+        }
+    }
+
+    #[test]
+    fn test_undetermined_variation() {
+        let rule = GameRule::new(true);
+        let pv = evaluate_board_with_pv(Board::new(), Color::Red, 2, rule).unwrap();
+        assert_eq!(pv.value(), None);
+        assert!(pv.actions().is_empty());
+        assert!(pv.to_string().starts_with("unknown"));
+    }
+}
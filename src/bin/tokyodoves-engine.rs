@@ -0,0 +1,570 @@
+//! A line-based text protocol engine of Tokyo Doves in the spirit of UCI of chess.
+//!
+//! The engine reads commands from the standard input line by line
+//! and writes responses to the standard output.
+//! Boards are written in the string expression accepted by `BoardBuilder::from_str`
+//! using `-` for vacant squares (e.g. `b---;B---`), and actions are written in SSN.
+//!
+//! # Commands
+//! - `tdi`<br>
+//!     Prints the name of the engine followed by `tdiok`.
+//! - `isready`<br>
+//!     Prints `readyok`.
+//! - `rule [remove <true|false>] [judge <lastwins|nextwins|draw>]`<br>
+//!     Sets whether removing doves is accepted and who wins
+//!     when both bosses are surrounded simultaneously.
+//!     The new rule takes effect from the next `position` command.
+//! - `position startpos [moves <ssn>...]`<br>
+//!     Sets the initial position of the rule and performs the actions.
+//! - `position board <board> [player <red|green>] [moves <ssn>...]`<br>
+//!     Sets the position of the board with the next player (red by default)
+//!     and performs the actions.
+//! - `go [depth <n>] [movetime <ms>]`<br>
+//!     Starts a search in the background.
+//!     It searches deeper and deeper until the value of the position is determined,
+//!     the depth reaches `n` or the time exceeds `ms` milliseconds.
+//!     A line `info depth <d> time <ms> nodes <n> nps <n> lower <value> upper <value>`
+//!     is printed every time a search of some depth is completed,
+//!     and `bestmove <ssn>` is printed when the search finishes.
+//! - `stop`<br>
+//!     Stops the search and prints `bestmove <ssn>` found so far.
+//! - `display`<br>
+//!     Prints the current position.
+//! - `quit`<br>
+//!     Exits immediately.
+//!
+//! Errors are reported as `info string <message>`.
+//! When the input is closed, the engine exits after the running search finishes.
+
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    mpsc::{self, Sender},
+    Arc,
+};
+use std::time::Duration;
+
+use tokyodoves::analysis::{
+    find_best_actions_with_options, Interval, SearchLimit, SearchOptions, SearchStats,
+};
+use tokyodoves::game::{Game, GameRule, Judge};
+use tokyodoves::{Action, BoardBuilder, Color};
+
+const ENGINE_NAME: &str = concat!("tokyodoves-engine ", env!("CARGO_PKG_VERSION"));
+
+/// Commands sent to the engine
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Command {
+    Tdi,
+    IsReady,
+    Rule {
+        remove: Option<bool>,
+        judge: Option<Judge>,
+    },
+    Position {
+        /// `None` means the initial position of the rule
+        board: Option<(String, Color)>,
+        moves: Vec<String>,
+    },
+    Go {
+        depth: Option<usize>,
+        movetime: Option<Duration>,
+    },
+    Stop,
+    Display,
+    Quit,
+}
+
+impl FromStr for Command {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tokens = s.split_whitespace();
+        let Some(name) = tokens.next() else {
+            return Err("empty command".to_string());
+        };
+        let command = match name {
+            "tdi" => Command::Tdi,
+            "isready" => Command::IsReady,
+            "rule" => {
+                let (mut remove, mut judge) = (None, None);
+                while let Some(key) = tokens.next() {
+                    let value = next_value(&mut tokens, key)?;
+                    match key {
+                        "remove" => remove = Some(parse_value(key, value)?),
+                        "judge" => judge = Some(parse_judge(value)?),
+                        _ => return Err(format!("unknown rule: {key}")),
+                    }
+                }
+                Command::Rule { remove, judge }
+            }
+            "position" => {
+                let mut board = match tokens.next() {
+                    Some("startpos") => None,
+                    Some("board") => {
+                        let board = next_value(&mut tokens, "board")?.to_string();
+                        Some((board, Color::Red))
+                    }
+                    _ => return Err("position requires startpos or board".to_string()),
+                };
+                let mut moves = Vec::new();
+                while let Some(key) = tokens.next() {
+                    match (key, board.as_mut()) {
+                        ("player", Some((_, player))) => {
+                            *player = parse_color(next_value(&mut tokens, key)?)?;
+                        }
+                        ("moves", _) => moves.extend(tokens.by_ref().map(str::to_string)),
+                        _ => return Err(format!("unexpected token: {key}")),
+                    }
+                }
+                Command::Position { board, moves }
+            }
+            "go" => {
+                let (mut depth, mut movetime) = (None, None);
+                while let Some(key) = tokens.next() {
+                    let value = next_value(&mut tokens, key)?;
+                    match key {
+                        "depth" => match parse_value(key, value)? {
+                            0 => return Err("depth must be positive".to_string()),
+                            d => depth = Some(d),
+                        },
+                        "movetime" => {
+                            movetime = Some(Duration::from_millis(parse_value(key, value)?));
+                        }
+                        _ => return Err(format!("unknown limit: {key}")),
+                    }
+                }
+                Command::Go { depth, movetime }
+            }
+            "stop" => Command::Stop,
+            "display" => Command::Display,
+            "quit" => Command::Quit,
+            _ => return Err(format!("unknown command: {name}")),
+        };
+        if let Some(token) = tokens.next() {
+            return Err(format!("unexpected token: {token}"));
+        }
+        Ok(command)
+    }
+}
+
+fn next_value<'a>(
+    tokens: &mut impl Iterator<Item = &'a str>,
+    key: &str,
+) -> Result<&'a str, String> {
+    tokens.next().ok_or(format!("{key} requires a value"))
+}
+
+fn parse_value<T: FromStr>(key: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value of {key}: {value}"))
+}
+
+fn parse_judge(s: &str) -> Result<Judge, String> {
+    match s {
+        "lastwins" => Ok(Judge::LastWins),
+        "nextwins" => Ok(Judge::NextWins),
+        "draw" => Ok(Judge::Draw),
+        _ => Err(format!("invalid judge: {s}")),
+    }
+}
+
+fn parse_color(s: &str) -> Result<Color, String> {
+    match s {
+        "red" => Ok(Color::Red),
+        "green" => Ok(Color::Green),
+        _ => Err(format!("invalid player: {s}")),
+    }
+}
+
+/// Events handled in the main loop
+enum Event {
+    Line(String),
+    Eof,
+    /// A search of `depth` completed
+    Depth {
+        id: usize,
+        depth: usize,
+        interval: Interval,
+        stats: SearchStats,
+    },
+    /// A search finished with the best actions of the deepest search completed
+    Finished {
+        id: usize,
+        actions: Vec<Action>,
+    },
+}
+
+/// A search running in the background
+struct Search {
+    id: usize,
+    cancel_flag: Arc<AtomicBool>,
+}
+
+struct Engine {
+    rule: GameRule,
+    game: Game,
+    search: Option<Search>,
+    num_searches: usize,
+    sender: Sender<Event>,
+}
+
+impl Engine {
+    fn new(sender: Sender<Event>) -> Self {
+        let rule = GameRule::new(true);
+        Self {
+            rule,
+            game: Game::new_with_rule(rule),
+            search: None,
+            num_searches: 0,
+            sender,
+        }
+    }
+
+    fn is_searching(&self) -> bool {
+        self.search.is_some()
+    }
+
+    /// Returns `true` if the search of `id` is running.
+    fn is_current(&self, id: usize) -> bool {
+        self.search.as_ref().is_some_and(|search| search.id == id)
+    }
+
+    /// Handles a line of the input and returns `false` if the engine should quit.
+    fn handle_line(&mut self, line: &str, out: &mut impl Write) -> std::io::Result<bool> {
+        if line.trim().is_empty() {
+            return Ok(true);
+        }
+        let command = match Command::from_str(line) {
+            Ok(command) => command,
+            Err(message) => {
+                writeln!(out, "info string {message}")?;
+                return Ok(true);
+            }
+        };
+        let is_allowed_while_searching = matches!(
+            command,
+            Command::IsReady | Command::Stop | Command::Display | Command::Quit
+        );
+        if self.is_searching() && !is_allowed_while_searching {
+            writeln!(out, "info string search in progress")?;
+            return Ok(true);
+        }
+
+        match command {
+            Command::Tdi => {
+                writeln!(out, "id name {ENGINE_NAME}")?;
+                writeln!(out, "tdiok")?;
+            }
+            Command::IsReady => writeln!(out, "readyok")?,
+            Command::Rule { remove, judge } => {
+                if let Some(remove) = remove {
+                    self.rule = self.rule.with_is_remove_accepted(remove);
+                }
+                if let Some(judge) = judge {
+                    self.rule = self.rule.with_suicide_atk_judge(judge);
+                }
+            }
+            Command::Position { board, moves } => {
+                if let Err(message) = self.set_position(board, &moves) {
+                    writeln!(out, "info string {message}")?;
+                }
+            }
+            Command::Go { depth, movetime } => self.go(depth, movetime, out)?,
+            Command::Stop => self.stop(),
+            Command::Display => {
+                let player = format!("{:?}", self.game.next_player()).to_lowercase();
+                let board = self.game.board().to_simple_string('-', ";");
+                writeln!(out, "info string board {board} player {player}")?;
+            }
+            Command::Quit => {
+                self.cancel();
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    fn set_position(
+        &mut self,
+        board: Option<(String, Color)>,
+        moves: &[String],
+    ) -> Result<(), String> {
+        let rule = match board {
+            Some((board, player)) => {
+                let board = BoardBuilder::from_str(&board)
+                    .and_then(|builder| builder.build())
+                    .map_err(|e| format!("invalid board: {e}"))?;
+                self.rule
+                    .with_initial_board(board)
+                    .map_err(|e| format!("invalid board: {e}"))?
+                    .with_first_player(player)
+            }
+            None => self.rule,
+        };
+        let mut game = Game::new_with_rule(rule);
+        for ssn in moves {
+            Action::try_from_ssn(ssn, game.board())
+                .and_then(|action| game.perform(action))
+                .map_err(|e| format!("invalid move {ssn}: {e}"))?;
+        }
+        self.game = game;
+        Ok(())
+    }
+
+    fn go(
+        &mut self,
+        depth: Option<usize>,
+        movetime: Option<Duration>,
+        out: &mut impl Write,
+    ) -> std::io::Result<()> {
+        if !self.game.is_ongoing() {
+            writeln!(out, "info string game already finished")?;
+            return writeln!(out, "bestmove none");
+        }
+
+        self.num_searches += 1;
+        let id = self.num_searches;
+        let cancel_flag = Arc::new(AtomicBool::new(false));
+        let mut limit = SearchLimit::new().with_cancel_flag(Arc::clone(&cancel_flag));
+        if let Some(movetime) = movetime {
+            limit = limit.with_time_limit(movetime);
+        }
+        self.search = Some(Search { id, cancel_flag });
+
+        let (board, player, rule) = (
+            *self.game.board(),
+            *self.game.next_player(),
+            *self.game.rule(),
+        );
+        let sender = self.sender.clone();
+        std::thread::spawn(move || {
+            let progress_sender = sender.clone();
+            let options = SearchOptions::new()
+                .with_limit(limit)
+                .with_progress(move |progress| {
+                    let _ = progress_sender.send(Event::Depth {
+                        id,
+                        depth: progress.depth(),
+                        interval: *progress.result(),
+                        stats: *progress.stats(),
+                    });
+                });
+            let max_depth = depth.unwrap_or(usize::MAX);
+            let actions = find_best_actions_with_options(board, player, max_depth, rule, options)
+                .map(|result| result.into_result().1)
+                .unwrap_or_default();
+            let _ = sender.send(Event::Finished { id, actions });
+        });
+        Ok(())
+    }
+
+    fn handle_depth(
+        &mut self,
+        id: usize,
+        depth: usize,
+        interval: Interval,
+        stats: SearchStats,
+        out: &mut impl Write,
+    ) -> std::io::Result<()> {
+        if !self.is_current(id) {
+            return Ok(());
+        }
+        writeln!(
+            out,
+            "info depth {depth} time {0} nodes {1} nps {2:.0} lower {3} upper {4}",
+            stats.elapsed().as_millis(),
+            stats.nodes(),
+            stats.nodes_per_second(),
+            interval.left(),
+            interval.right(),
+        )
+    }
+
+    /// Prints the best action of the finished search.
+    fn finish(
+        &mut self,
+        id: usize,
+        actions: &[Action],
+        out: &mut impl Write,
+    ) -> std::io::Result<()> {
+        if !self.is_current(id) {
+            return Ok(());
+        }
+        self.search = None;
+        // Any legal action is better than nothing if no search has been completed
+        let best = actions
+            .first()
+            .copied()
+            .or_else(|| self.game.legal_actions().into_iter().next());
+        let ssn = best.and_then(|action| action.try_into_ssn(self.game.board()).ok());
+        writeln!(out, "bestmove {}", ssn.as_deref().unwrap_or("none"))
+    }
+
+    /// Stops the search, whose best action is printed when it finishes.
+    fn stop(&self) {
+        if let Some(search) = self.search.as_ref() {
+            search.cancel_flag.store(true, Ordering::Relaxed);
+        }
+    }
+
+    fn cancel(&mut self) {
+        self.stop();
+        self.search = None;
+    }
+}
+
+/// Runs the engine until `quit` is received or the input is closed.
+fn run<R, W>(input: R, out: &mut W) -> std::io::Result<()>
+where
+    R: BufRead + Send + 'static,
+    W: Write,
+{
+    let (sender, receiver) = mpsc::channel();
+    let input_sender = sender.clone();
+    std::thread::spawn(move || {
+        for line in input.lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if input_sender.send(Event::Line(line)).is_err() {
+                return;
+            }
+        }
+        let _ = input_sender.send(Event::Eof);
+    });
+
+    let mut engine = Engine::new(sender);
+    let mut is_closed = false;
+    while let Ok(event) = receiver.recv() {
+        match event {
+            Event::Line(line) => {
+                if !engine.handle_line(&line, out)? {
+                    break;
+                }
+            }
+            Event::Eof => is_closed = true,
+            Event::Depth {
+                id,
+                depth,
+                interval,
+                stats,
+            } => engine.handle_depth(id, depth, interval, stats, out)?,
+            Event::Finished { id, actions } => engine.finish(id, &actions, out)?,
+        }
+        out.flush()?;
+        if is_closed && !engine.is_searching() {
+            break;
+        }
+    }
+    engine.cancel();
+    Ok(())
+}
+
+fn main() -> std::io::Result<()> {
+    let stdin = std::io::BufReader::new(std::io::stdin());
+    run(stdin, &mut std::io::stdout())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_lines(lines: &[&str]) -> Vec<String> {
+        let input = std::io::Cursor::new(lines.join("\n").into_bytes());
+        let mut out = Vec::new();
+        run(input, &mut out).unwrap();
+        String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(
+            Command::from_str("rule remove false judge draw"),
+            Ok(Command::Rule {
+                remove: Some(false),
+                judge: Some(Judge::Draw),
+            })
+        );
+        assert_eq!(
+            Command::from_str("position board bB;T-YA player green moves +AS1 -A"),
+            Ok(Command::Position {
+                board: Some(("bB;T-YA".to_string(), Color::Green)),
+                moves: vec!["+AS1".to_string(), "-A".to_string()],
+            })
+        );
+        assert_eq!(
+            Command::from_str("go depth 3 movetime 100"),
+            Ok(Command::Go {
+                depth: Some(3),
+                movetime: Some(Duration::from_millis(100)),
+            })
+        );
+        for s in [
+            "",
+            "go depth",
+            "go depth 0",
+            "position startpos player red",
+            "rule x 1",
+        ] {
+            assert!(Command::from_str(s).is_err());
+        }
+    }
+
+    #[test]
+    fn test_find_mate() {
+        let out = run_lines(&["position board bB;T-YA", "go depth 7"]);
+        let board = BoardBuilder::from_str("bB;T-YA").unwrap().build().unwrap();
+        let rule = GameRule::new(true);
+        let best = tokyodoves::analysis::find_best_actions(board, Color::Red, 5, rule).unwrap();
+        let ssn = best[0].try_into_ssn(&board).unwrap();
+        assert_eq!(*out.last().unwrap(), format!("bestmove {ssn}"));
+        // The search stops at depth 5 because the value is determined
+        assert!(out[out.len() - 2].starts_with("info depth 5 "));
+        assert!(out[out.len() - 2].ends_with("lower Win(5) upper Win(5)"));
+    }
+
+    #[test]
+    fn test_rule_after_position() {
+        let nodes = |lines: &[&str]| {
+            let out = run_lines(lines);
+            let info = &out[out.len() - 2];
+            let mut words = info.split_whitespace().skip_while(|w| *w != "nodes");
+            words.nth(1).unwrap().to_string()
+        };
+        // The new rule takes effect from the next `position` command
+        let pending = nodes(&["position board bB;T-YA", "rule judge draw", "go depth 5"]);
+        assert_eq!(pending, nodes(&["position board bB;T-YA", "go depth 5"]));
+        let applied = nodes(&["rule judge draw", "position board bB;T-YA", "go depth 5"]);
+        assert_ne!(pending, applied);
+    }
+
+    #[test]
+    fn test_position_with_moves() {
+        let out = run_lines(&[
+            "tdi",
+            "position startpos moves +AS1 +aN2",
+            "display",
+            "position startpos moves +AS1 +AS2",
+            "go depth 1",
+        ]);
+        assert_eq!(out[0], format!("id name {ENGINE_NAME}"));
+        assert_eq!(out[1], "tdiok");
+        assert_eq!(out[2], "info string board a---;b---;B---;A--- player red");
+        assert!(out[3].starts_with("info string invalid move +AS2"));
+        assert!(out.last().unwrap().starts_with("bestmove "));
+    }
+
+    #[test]
+    fn test_movetime() {
+        let out = run_lines(&["position startpos", "go movetime 50", "isready"]);
+        assert!(out.contains(&"readyok".to_string()));
+        assert!(out.last().unwrap().starts_with("bestmove "));
+    }
+}
@@ -13,9 +13,14 @@
 //! - [`GameError`]
 //!     - GameRuleCreateError: [`GameRuleCreateErrorKind`]
 //!     - PlayingError: [`PlayingErrorKind`]
+//!     - HistoryError: [`HistoryErrorKind`]
+//!     - RecordError: [`RecordErrorKind`]
+//!     - TournamentError: [`TournamentErrorKind`]
+//!     - PositionError: [`PositionErrorKind`]
 //! - [`AnalysisError`]
 //!     - ArgsValidationError: [`ArgsValidationErrorKind`]
 //!     - BoardValueMismatch: [`std::cmp::Ordering`]
+//!     - PerftError: [`PerftErrorKind`]
 //!
 //! Some of contents are available only when feature "game" or "analysis" is indicated.
 
@@ -229,6 +234,7 @@ impl From<DecodingErrorKind> for Error {
 /// Errors associated to [`Game`](`crate::game::Game`) ("game" feature required)
 #[cfg(feature = "game")]
 #[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
 pub enum GameError {
     /// Errors on creating [`GameRule`](`crate::game::GameRule`)
     #[error("GameRuleCreateError::{kind:?}")]
@@ -237,13 +243,32 @@ pub enum GameError {
     /// Errors on playing games
     #[error("PlayingError::{kind:?}")]
     PlayingError { kind: PlayingErrorKind },
+
+    /// Errors on moving in the history of games
+    #[error("HistoryError::{kind:?}")]
+    HistoryError { kind: HistoryErrorKind },
+
+    /// Errors on loading records of games
+    #[error("RecordError::{kind:?}")]
+    RecordError { kind: RecordErrorKind },
+
+    /// Errors on running tournaments
+    #[error("TournamentError::{kind:?}")]
+    TournamentError { kind: TournamentErrorKind },
+
+    /// Errors on parsing positions of games
+    #[error("PositionError::{kind:?}")]
+    PositionError { kind: PositionErrorKind },
 }
 
 /// Error kinds on creating [`GameRule`](`crate::game::GameRule`) ("game" feature required)
 #[cfg(feature = "game")]
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum GameRuleCreateErrorKind {
     InitialBoardError,
+    RepetitionLimitError(usize),
+    MaxPliesError(usize),
 }
 
 #[cfg(feature = "game")]
@@ -269,9 +294,71 @@ impl From<PlayingErrorKind> for Error {
     }
 }
 
+/// Error kinds on moving in the history of games ("game" feature required)
+#[cfg(feature = "game")]
+#[derive(Debug)]
+pub enum HistoryErrorKind {
+    NothingToUndo,
+    NothingToRedo,
+    PlyOutOfRange(usize),
+}
+
+#[cfg(feature = "game")]
+impl From<HistoryErrorKind> for Error {
+    fn from(value: HistoryErrorKind) -> Self {
+        GameError::HistoryError { kind: value }.into()
+    }
+}
+
+/// Error kinds on loading records of games ("game" feature required)
+#[cfg(feature = "game")]
+#[derive(Debug)]
+pub enum RecordErrorKind {
+    UnknownKey(String),
+    InvalidValue(String),
+    InvalidAction(usize, String),
+}
+
+#[cfg(feature = "game")]
+impl From<RecordErrorKind> for Error {
+    fn from(value: RecordErrorKind) -> Self {
+        GameError::RecordError { kind: value }.into()
+    }
+}
+
+/// Error kinds on running tournaments ("game" feature required)
+#[cfg(feature = "game")]
+#[derive(Debug)]
+pub enum TournamentErrorKind {
+    TooFewAgents(usize),
+}
+
+#[cfg(feature = "game")]
+impl From<TournamentErrorKind> for Error {
+    fn from(value: TournamentErrorKind) -> Self {
+        GameError::TournamentError { kind: value }.into()
+    }
+}
+
+/// Error kinds on parsing positions of games ("game" feature required)
+#[cfg(feature = "game")]
+#[derive(Debug)]
+pub enum PositionErrorKind {
+    InvalidFormat(String),
+    InvalidValue(String),
+}
+
+#[cfg(feature = "game")]
+impl From<PositionErrorKind> for Error {
+    fn from(value: PositionErrorKind) -> Self {
+        GameError::PositionError { kind: value }.into()
+    }
+}
+
 /// Error variants on analysis for games ("analysis" feature required)
 #[cfg(feature = "analysis")]
 #[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
 pub enum AnalysisError {
     /// Errors on validating arguments
     #[error("ArgsValidationError::{kind}")]
@@ -280,15 +367,18 @@ pub enum AnalysisError {
     /// Errors for [`create_checkmate_tree_with_value`](`crate::analysis::create_checkmate_tree_with_value`)
     #[error("BoardValueMismatch: value of board is {0:?} than value in argument")]
     BoardValueMismatch(std::cmp::Ordering),
+
+    /// Errors for [`perft_checked`](`crate::analysis::perft_checked`)
+    #[error("PerftError::{kind:?}")]
+    PerftError { kind: PerftErrorKind },
 }
 
 /// Error kinds on validation of arguments ("analysis" feature required)
 #[cfg(feature = "analysis")]
 #[derive(Debug)]
 pub enum ArgsValidationErrorKind {
-    FinishedGameBoard(Board),
+    FinishedGameBoard(Box<Board>),
     UnsupportedValue(BoardValue),
-    DrawJudge,
 }
 
 #[cfg(feature = "analysis")]
@@ -298,6 +388,25 @@ impl From<ArgsValidationErrorKind> for Error {
     }
 }
 
+/// Error kinds on checking consistency of action generation ("analysis" feature required)
+///
+/// Each variant holds the board and the generated action that caused the error.
+#[cfg(feature = "analysis")]
+#[derive(Debug)]
+pub enum PerftErrorKind {
+    CheckActionFailed(Box<Board>, Action),
+    PerformFailed(Box<Board>, Action),
+    PerformMismatch(Box<Board>, Action),
+    InverseNotFound(Box<Board>, Action),
+}
+
+#[cfg(feature = "analysis")]
+impl From<PerftErrorKind> for Error {
+    fn from(value: PerftErrorKind) -> Self {
+        AnalysisError::PerftError { kind: value }.into()
+    }
+}
+
 #[cfg(feature = "analysis")]
 impl std::fmt::Display for ArgsValidationErrorKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -305,7 +414,6 @@ impl std::fmt::Display for ArgsValidationErrorKind {
         let msg = match self {
             FinishedGameBoard(_board) => String::from("FinishedGameBoard: board of finished game"),
             UnsupportedValue(value) => format!("UnsupportedValue: {value} not supported"),
-            DrawJudge => String::from("DrawJudge: Judge::Draw not supported"),
         };
         write!(f, "{msg}")
     }
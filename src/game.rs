@@ -8,8 +8,11 @@
 //!     The following agents are included:
 //!     - [`RandomAgent`]<br>
 //!         An agent who chooses the next action at random.
+//!         The probabilities of actions can be weighted by [`ActionWeight`].
 //!     - [`AnalystAgent`]<br>
 //!         An agent who chooses the next action based on some analyses.
+//!     - [`MctsAgent`]<br>
+//!         An agent who chooses the next action by Monte Carlo tree search.
 //!     - [`ConsoleAgent`]<br>
 //!         An agent who asks the next action to the console.
 //!         It provides CLI with humans.
@@ -18,14 +21,39 @@
 //!     by implementing the [`Agent`] trait if you want.
 //! - [`Arena`]<br>
 //!     A struct of an arena, where two [`Agent`]s play against.
+//! - [`Tournament`]<br>
+//!     A struct to let two or more [`Agent`]s play many games
+//!     and to summarize the results (see [`TournamentResult`]).
+//!
+//! [`Game`] can be saved as a record (kifu) in plain text
+//! by the [`to_record`](`Game::to_record`) method
+//! and restored by the [`from_record`](`Game::from_record`) method.
+//! A single position of a game, including the player to move next,
+//! can be written in one line by [`Position`]
+//! (see [`position`](`Game::position`) and [`from_position`](`Game::from_position`)).
 //!
 //! All entities in this module are available when "game" feature is indicated,
 //! except [`AnalystAgent`] which is available when "analysis" feature is indicated.
 
+mod mcts;
+mod position;
+mod record;
+mod tournament;
+
+pub use mcts::MctsAgent;
+pub use position::Position;
+pub use tournament::{EloEstimate, GameResult, Score, Tournament, TournamentResult, WinLossDraw};
+
 #[cfg(feature = "analysis")]
-use crate::analysis::{evaluate_board, find_best_actions};
+use crate::analysis::{
+    evaluate_board, find_best_actions_with_evaluator, find_best_actions_with_limit, BoardValue,
+    DefaultEvaluator, Evaluator, SearchLimit,
+};
 use crate::error;
 use crate::prelude::{Action, ActionContainer, ActionsFwd, Board, Color, SurroundedStatus};
+use rand::{distributions::WeightedIndex, rngs::StdRng, Rng, SeedableRng};
+#[cfg(feature = "analysis")]
+use std::time::Duration;
 
 // ************************************************************
 //  Building Blocks
@@ -48,10 +76,15 @@ use crate::prelude::{Action, ActionContainer, ActionsFwd, Board, Color, Surround
 /// // Set board you like as the initial board (requires error handling)
 /// let initial_board = BoardBuilder::from_str("B;bh")?.build()?;
 /// let rule = GameRule::default().with_initial_board(initial_board)?;
+/// // Finish the game as a draw on threefold repetition or after 200 actions
+/// let rule = GameRule::default()
+///     .with_repetition_limit(Some(3))?
+///     .with_max_plies(Some(200))?;
 /// # Ok(())
 /// # }
 /// ```
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct GameRule {
     /// Allow [`Action::Remove`] as a legal action or not
     is_remove_accepted: bool,
@@ -61,6 +94,51 @@ pub struct GameRule {
     suicide_atk_judge: Judge,
     /// Initial board
     initial_board: Board,
+    /// The number of occurrences of the same state that finishes the game
+    repetition_limit: Option<usize>,
+    /// Winner judgement when the game is finished by repetition
+    repetition_judge: Judge,
+    /// The number of actions that finishes the game
+    max_plies: Option<usize>,
+    /// Winner judgement when the game is finished by the number of actions
+    max_plies_judge: Judge,
+}
+
+/// Deserializes the rule in the same encoding as [`Serialize`](`serde::Serialize`),
+/// checking that the initial board and the limits are valid as the builder methods
+/// such as [`GameRule::with_initial_board`] do.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for GameRule {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "GameRule")]
+        struct Fields {
+            is_remove_accepted: bool,
+            first_player: Color,
+            suicide_atk_judge: Judge,
+            initial_board: Board,
+            repetition_limit: Option<usize>,
+            repetition_judge: Judge,
+            max_plies: Option<usize>,
+            max_plies_judge: Judge,
+        }
+
+        let fields = Fields::deserialize(deserializer)?;
+        let rule = GameRule::new(fields.is_remove_accepted)
+            .with_first_player(fields.first_player)
+            .with_suicide_atk_judge(fields.suicide_atk_judge)
+            .with_initial_board(fields.initial_board)
+            .map_err(D::Error::custom)?
+            .with_repetition_limit(fields.repetition_limit)
+            .map_err(D::Error::custom)?
+            .with_repetition_judge(fields.repetition_judge)
+            .with_max_plies(fields.max_plies)
+            .map_err(D::Error::custom)?
+            .with_max_plies_judge(fields.max_plies_judge);
+        Ok(rule)
+    }
 }
 
 impl GameRule {
@@ -73,6 +151,14 @@ impl GameRule {
     ///     ... `Judge::NextWins` (the next player who just moved the dove wins)
     /// - `initial_board`<br>
     ///     ... `Board::new()`
+    /// - `repetition_limit` (how many times the same state may occur)<br>
+    ///     ... `None` (no limit)
+    /// - `repetition_judge` (who wins if the game is finished by repetition)<br>
+    ///     ... `Judge::Draw`
+    /// - `max_plies` (how many actions may be performed)<br>
+    ///     ... `None` (no limit)
+    /// - `max_plies_judge` (who wins if the game is finished by the number of actions)<br>
+    ///     ... `Judge::Draw`
     ///
     /// # Examples
     /// ```rust
@@ -88,6 +174,10 @@ impl GameRule {
             first_player,
             suicide_atk_judge,
             initial_board,
+            repetition_limit: None,
+            repetition_judge: Judge::Draw,
+            max_plies: None,
+            max_plies_judge: Judge::Draw,
         }
     }
 
@@ -144,6 +234,68 @@ impl GameRule {
         &self.initial_board
     }
 
+    /// Returns the number of occurrences of the same state that finishes the game.
+    ///
+    /// The state consists of the board and the next player.
+    /// Boards coinciding with each other under translation are regarded as the same.
+    /// `None` means that the game is never finished by repetition.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tokyodoves::game::GameRule;
+    ///
+    /// let rule = GameRule::new(true);
+    /// assert!(rule.repetition_limit().is_none());
+    /// ```
+    pub fn repetition_limit(&self) -> &Option<usize> {
+        &self.repetition_limit
+    }
+
+    /// Returns judgement when the game is finished by repetition.
+    ///
+    /// The "event" of [`Judge`] is the action that caused the repetition.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tokyodoves::game::{GameRule, Judge};
+    ///
+    /// let rule = GameRule::new(true);
+    /// assert!(matches!(rule.repetition_judge(), Judge::Draw));
+    /// ```
+    pub fn repetition_judge(&self) -> &Judge {
+        &self.repetition_judge
+    }
+
+    /// Returns the number of actions that finishes the game.
+    ///
+    /// `None` means that there is no limit on the number of actions.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tokyodoves::game::GameRule;
+    ///
+    /// let rule = GameRule::new(true);
+    /// assert!(rule.max_plies().is_none());
+    /// ```
+    pub fn max_plies(&self) -> &Option<usize> {
+        &self.max_plies
+    }
+
+    /// Returns judgement when the game is finished by the number of actions.
+    ///
+    /// The "event" of [`Judge`] is the last action allowed.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tokyodoves::game::{GameRule, Judge};
+    ///
+    /// let rule = GameRule::new(true);
+    /// assert!(matches!(rule.max_plies_judge(), Judge::Draw));
+    /// ```
+    pub fn max_plies_judge(&self) -> &Judge {
+        &self.max_plies_judge
+    }
+
     /// Update whether accept `Remove` in the game or not.
     ///
     /// # Examples
@@ -217,6 +369,102 @@ impl GameRule {
         };
         Ok(rule)
     }
+
+    /// Update the number of occurrences of the same state that finishes the game.
+    ///
+    /// # Errors
+    /// It returns:
+    /// - `Err(error::GameRuleCreateErrorKind::RepetitionLimitError(n).into())`
+    ///     if `repetition_limit` is `Some(n)` with `n` less than 2,
+    ///     because every state occurs at least once
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tokyodoves::game::GameRule;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let rule = GameRule::new(true).with_repetition_limit(Some(3))?;
+    /// assert!(GameRule::new(true).with_repetition_limit(Some(1)).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_repetition_limit(
+        self,
+        repetition_limit: Option<usize>,
+    ) -> Result<Self, error::Error> {
+        if let Some(n @ 0..=1) = repetition_limit {
+            return Err(error::GameRuleCreateErrorKind::RepetitionLimitError(n).into());
+        }
+        let rule = Self {
+            repetition_limit,
+            ..self
+        };
+        Ok(rule)
+    }
+
+    /// Update judgement rule when the game is finished by repetition.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tokyodoves::game::{GameRule, Judge};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let rule = GameRule::new(true)
+    ///     .with_repetition_limit(Some(3))?
+    ///     .with_repetition_judge(Judge::NextWins);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_repetition_judge(self, judge: Judge) -> Self {
+        Self {
+            repetition_judge: judge,
+            ..self
+        }
+    }
+
+    /// Update the number of actions that finishes the game.
+    ///
+    /// # Errors
+    /// It returns:
+    /// - `Err(error::GameRuleCreateErrorKind::MaxPliesError(0).into())`
+    ///     if `max_plies` is `Some(0)`, because a game cannot finish without any action
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tokyodoves::game::GameRule;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let rule = GameRule::new(true).with_max_plies(Some(200))?;
+    /// assert!(GameRule::new(true).with_max_plies(Some(0)).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_max_plies(self, max_plies: Option<usize>) -> Result<Self, error::Error> {
+        if let Some(n @ 0) = max_plies {
+            return Err(error::GameRuleCreateErrorKind::MaxPliesError(n).into());
+        }
+        Ok(Self { max_plies, ..self })
+    }
+
+    /// Update judgement rule when the game is finished by the number of actions.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tokyodoves::game::{GameRule, Judge};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let rule = GameRule::new(true)
+    ///     .with_max_plies(Some(200))?
+    ///     .with_max_plies_judge(Judge::LastWins);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_max_plies_judge(self, judge: Judge) -> Self {
+        Self {
+            max_plies_judge: judge,
+            ..self
+        }
+    }
 }
 
 impl Default for GameRule {
@@ -232,6 +480,7 @@ impl Default for GameRule {
 
 /// Judgement of winner on some event
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Judge {
     /// The player just before the event is treated as the winner
     LastWins,
@@ -241,8 +490,21 @@ pub enum Judge {
     Draw,
 }
 
+impl Judge {
+    /// Returns the winner when `last_player` has just caused the event.
+    fn winner(&self, last_player: Color) -> Option<Color> {
+        match self {
+            Judge::LastWins => Some(last_player),
+            Judge::NextWins => Some(!last_player),
+            Judge::Draw => None,
+        }
+    }
+}
+
 /// Status of the game
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 pub enum GameStatus {
     /// The game is ongoing
     Ongoing,
@@ -250,6 +512,21 @@ pub enum GameStatus {
     Win(Color),
     /// The game has already finished; it was a draw game
     Draw,
+    /// The game has already finished by a limitation in [`GameRule`]
+    /// before any boss was surrounded; the winner is also given if determined
+    Terminated(Termination, Option<Color>),
+}
+
+/// Reasons for finishing games without surrounding bosses
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Termination {
+    /// The same state occurred as many times as
+    /// [`repetition_limit`](`GameRule::repetition_limit`)
+    Repetition,
+    /// Actions were performed as many times as
+    /// [`max_plies`](`GameRule::max_plies`)
+    MaxPlies,
 }
 
 // ************************************************************
@@ -310,12 +587,68 @@ pub enum GameStatus {
 /// For more information about the default value of [`GameRule`],
 /// see the [documentation](`GameRule::default`) about the implementation of
 /// [`Default`] trait for `GameRule`.
-#[derive(Debug, Clone, Copy)]
+///
+/// [`Game`] records every performed [`Action`] so that you can take them back
+/// by the [`undo`](`Game::undo`), [`redo`](`Game::redo`)
+/// and [`jump_to`](`Game::jump_to`) methods:
+/// ```rust
+/// use tokyodoves::{Action, Color, Dove, Shift};
+/// use tokyodoves::game::Game;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut game = Game::new(true);
+/// game.perform(Action::Put(Color::Red, Dove::A, Shift::new(0, 1)))?;
+/// game.perform(Action::Put(Color::Green, Dove::A, Shift::new(0, 1)))?;
+/// assert_eq!(game.ply(), 2);
+///
+/// game.undo()?;
+/// assert_eq!(game.ply(), 1);
+/// assert_eq!(*game.next_player(), Color::Green);
+///
+/// game.jump_to(0)?;
+/// assert_eq!(*game.board(), *game.rule().initial_board());
+///
+/// game.redo()?;
+/// game.redo()?;
+/// assert_eq!(game.ply(), 2);
+/// assert_eq!(game.history().len(), 2);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Since [`Game`] owns its history, it implements [`Clone`] but not [`Copy`].
+/// Use [`Game::position`] to obtain a [`Copy`] snapshot of the current state.
+#[derive(Debug, Clone)]
 pub struct Game {
+    rule: GameRule,
+    /// Recorded actions (including those taken back and not yet redone)
+    history: Vec<Action>,
+    /// `states[i]` is the state after `i` actions in `history` were performed
+    states: Vec<GameState>,
+    /// The number of actions in `history` that have been performed
+    ply: usize,
+}
+
+/// A snapshot of the variable part of [`Game`]
+#[derive(Debug, Clone, Copy)]
+struct GameState {
     board: Board,
     player: Color,
     status: GameStatus,
-    rule: GameRule,
+    /// A key to detect repetition of states
+    key: u64,
+}
+
+impl GameState {
+    fn new(board: Board, player: Color, status: GameStatus) -> Self {
+        let key = board.to_translation_invariant_u64();
+        Self {
+            board,
+            player,
+            status,
+            key,
+        }
+    }
 }
 
 impl Game {
@@ -330,18 +663,18 @@ impl Game {
 
     /// Constructs [`Game`] with a specified `rule`
     pub fn new_with_rule(rule: GameRule) -> Game {
-        let board = rule.initial_board;
-        let player = rule.first_player;
-        let status = GameStatus::Ongoing;
+        let state = GameState::new(rule.initial_board, rule.first_player, GameStatus::Ongoing);
         Game {
-            board,
-            player,
-            status,
             rule,
+            history: Vec::new(),
+            states: vec![state],
+            ply: 0,
         }
     }
 
     /// Reset [`Game`] to the initial state
+    ///
+    /// The recorded history is also cleared.
     pub fn reset(&mut self) {
         *self = Self::new_with_rule(self.rule)
     }
@@ -353,22 +686,26 @@ impl Game {
 
     /// Get a reference to board
     pub fn board(&self) -> &Board {
-        &self.board
+        &self.state().board
     }
 
     /// Get a reference to the next player
     pub fn next_player(&self) -> &Color {
-        &self.player
+        &self.state().player
     }
 
     /// Get a reference to status
     pub fn status(&self) -> &GameStatus {
-        &self.status
+        &self.state().status
     }
 
     /// Returns `true` if the game is ongoing
     pub fn is_ongoing(&self) -> bool {
-        matches!(self.status, GameStatus::Ongoing)
+        matches!(self.status(), GameStatus::Ongoing)
+    }
+
+    fn state(&self) -> &GameState {
+        &self.states[self.ply]
     }
 
     /// Returns winner.
@@ -377,16 +714,115 @@ impl Game {
     /// It returns `None` if the game is ongoing or draw.
     pub fn winner(&self) -> Option<Color> {
         use GameStatus::*;
-        match self.status {
+        match *self.status() {
             Ongoing | Draw => None,
             Win(player) => Some(player),
+            Terminated(_, winner) => winner,
+        }
+    }
+
+    /// Returns the recorded [`Action`]s from the beginning of the game.
+    ///
+    /// It also contains actions taken back by the [`undo`](`Self::undo`) method
+    /// as long as they are not overwritten by the [`perform`](`Self::perform`) method.
+    /// The current position in the history is returned by the [`ply`](`Self::ply`) method,
+    /// i.e., the current board is the result of performing `history()[..ply()]`.
+    pub fn history(&self) -> &[Action] {
+        &self.history
+    }
+
+    /// Returns the number of [`Action`]s performed from the beginning of the game
+    /// to reach the current state.
+    pub fn ply(&self) -> usize {
+        self.ply
+    }
+
+    /// Takes back the last [`Action`] and returns it.
+    ///
+    /// The board, the next player and the status are restored
+    /// to those just before the action was performed.
+    /// The action is kept in the history so that it can be performed again
+    /// by the [`redo`](`Self::redo`) method.
+    ///
+    /// # Errors
+    /// It returns:
+    /// - `Err(error::HistoryErrorKind::NothingToUndo.into())` if no action has been performed.
+    pub fn undo(&mut self) -> Result<Action, error::Error> {
+        if self.ply == 0 {
+            return Err(error::HistoryErrorKind::NothingToUndo.into());
         }
+        let action = self.history[self.ply - 1];
+        self.ply -= 1;
+        Ok(action)
+    }
+
+    /// Performs the [`Action`] taken back by the [`undo`](`Self::undo`) method again
+    /// and returns it.
+    ///
+    /// # Errors
+    /// It returns:
+    /// - `Err(error::HistoryErrorKind::NothingToRedo.into())` if there is no action to redo.
+    pub fn redo(&mut self) -> Result<Action, error::Error> {
+        if self.ply == self.history.len() {
+            return Err(error::HistoryErrorKind::NothingToRedo.into());
+        }
+        let action = self.history[self.ply];
+        self.ply += 1;
+        Ok(action)
+    }
+
+    /// Moves to the state after `ply` actions in the history were performed.
+    ///
+    /// `jump_to(0)` restores the state at the beginning of the game.
+    ///
+    /// # Errors
+    /// It returns:
+    /// - `Err(error::HistoryErrorKind::PlyOutOfRange(ply).into())`
+    ///     if `ply` is greater than the length of the [`history`](`Self::history`).
+    pub fn jump_to(&mut self, ply: usize) -> Result<(), error::Error> {
+        if ply > self.history.len() {
+            return Err(error::HistoryErrorKind::PlyOutOfRange(ply).into());
+        }
+        self.ply = ply;
+        Ok(())
+    }
+
+    fn record(&mut self, action: Action, state: GameState) {
+        self.history.truncate(self.ply);
+        self.states.truncate(self.ply + 1);
+        self.history.push(action);
+        self.states.push(state);
+        self.ply += 1;
+    }
+
+    /// Judges whether the game should be terminated by limitations in [`GameRule`]
+    /// after the last action was recorded.
+    fn judge_termination(&mut self) {
+        let current = self.state();
+        let last_player = !current.player;
+        let is_repeated = self.rule.repetition_limit.is_some_and(|limit| {
+            let count = self.states[..=self.ply]
+                .iter()
+                .filter(|state| state.key == current.key && state.player == current.player)
+                .count();
+            count >= limit
+        });
+        let termination = if is_repeated {
+            let winner = self.rule.repetition_judge.winner(last_player);
+            GameStatus::Terminated(Termination::Repetition, winner)
+        } else if self.rule.max_plies.is_some_and(|max| self.ply >= max) {
+            let winner = self.rule.max_plies_judge.winner(last_player);
+            GameStatus::Terminated(Termination::MaxPlies, winner)
+        } else {
+            return;
+        };
+        self.states[self.ply].status = termination;
     }
 
     /// Checks if the specified [`Action`] is legal
     pub fn check_action(&self, action: Action) -> Result<(), error::Error> {
         use error::PlayingErrorKind::*;
-        if self.player != *action.player() {
+        if *self.next_player() != *action.player() {
             return Err(PlayerMismatch.into());
         }
 
@@ -400,8 +836,12 @@ impl Game {
 
     /// Returns an [`ActionContainer`](`super::board::container::ActionContainer`) of legal [`Action`]s.
     pub fn legal_actions(&self) -> ActionsFwd {
-        self.board
-            .legal_actions(self.player, true, true, self.rule.is_remove_accepted)
+        self.board().legal_actions(
+            *self.next_player(),
+            true,
+            true,
+            self.rule.is_remove_accepted,
+        )
     }
 
     /// Performs specified [`Action`].
@@ -410,6 +850,14 @@ impl Game {
     /// a game end judgement is made.
     /// If it is determined that the game should continue,
     /// the turn moves to the next player.
+    /// After that, the game may be terminated by
+    /// [`repetition_limit`](`GameRule::repetition_limit`) or
+    /// [`max_plies`](`GameRule::max_plies`) of its rule,
+    /// which results in [`GameStatus::Terminated`].
+    ///
+    /// The action is appended to the [`history`](`Self::history`).
+    /// If some actions have been taken back by the [`undo`](`Self::undo`) method,
+    /// they are discarded from the history.
     ///
     /// # Errors
     /// It returns:
@@ -423,21 +871,31 @@ impl Game {
     /// In any cases, [`Game`] object is left unchanged.
     pub fn perform(&mut self, action: Action) -> Result<(), error::Error> {
         if !self.is_ongoing() {
-            return Err(error::PlayingErrorKind::GameFinished(self.status).into());
+            return Err(error::PlayingErrorKind::GameFinished(*self.status()).into());
         }
         self.check_action(action)?;
-        self.board.perform_unchecked(action);
+        let GameState {
+            mut board, player, ..
+        } = *self.state();
+        board.perform_unchecked(action);
 
         use GameStatus::*;
         use SurroundedStatus::*;
-        match self.board.surrounded_status() {
+        let status = match board.surrounded_status() {
             Both => match self.rule.suicide_atk_judge {
-                Judge::LastWins => self.status = Win(self.player),
-                Judge::NextWins => self.status = Win(!self.player),
-                Judge::Draw => self.status = Draw,
+                Judge::LastWins => Win(player),
+                Judge::NextWins => Win(!player),
+                Judge::Draw => Draw,
             },
-            OneSide(player) => self.status = Win(!player),
-            None => self.player = !self.player,
+            OneSide(loser) => Win(!loser),
+            None => Ongoing,
+        };
+        // The turn moves to the next player only if the game continues
+        let is_surrounded = status != Ongoing;
+        let next_player = if is_surrounded { player } else { !player };
+        self.record(action, GameState::new(board, next_player, status));
+        if !is_surrounded {
+            self.judge_termination();
         }
 
         Ok(())
@@ -546,6 +1004,7 @@ impl<'a> std::fmt::Display for GameDisplay<'a> {
 /// An agant who plays the game.
 ///
 /// [`Arena`] receives two agetns and let them play the game.
+/// [`Tournament`] receives two or more agents and let them play many games.
 pub trait Agent {
     /// Performs some action on board.
     ///
@@ -554,10 +1013,142 @@ pub trait Agent {
     fn play(&mut self, game: &mut Game);
 }
 
+/// A weight of an [`Action`] used to choose actions at random.
+///
+/// It is used by [`RandomAgent`] to prefer some actions to others:
+/// an action is chosen with a probability proportional to its weight.
+/// Any function or closure with a signature `Fn(&Board, Color, Action) -> f64`
+/// implements this trait.
+///
+/// # Examples
+/// ```rust
+/// use tokyodoves::{Action, Board, Color, SurroundedStatus};
+/// use tokyodoves::game::RandomAgent;
+///
+/// // Strongly prefer actions surrounding the opponent's boss
+/// let weight = |board: &Board, player: Color, action: Action| {
+///     let next_board = board.perform_unchecked_copied(action);
+///     match next_board.surrounded_status() {
+///         SurroundedStatus::OneSide(color) if color != player => 100.0,
+///         _ => 1.0,
+///     }
+/// };
+/// let agent = RandomAgent::new().with_weight(weight);
+/// ```
+pub trait ActionWeight {
+    /// Returns the weight of `action` performed by `player` on `board`.
+    ///
+    /// The weight must be non-negative and finite.
+    fn weight(&self, board: &Board, player: Color, action: Action) -> f64;
+}
+
+impl<F> ActionWeight for F
+where
+    F: Fn(&Board, Color, Action) -> f64,
+{
+    fn weight(&self, board: &Board, player: Color, action: Action) -> f64 {
+        self(board, player, action)
+    }
+}
+
+/// An [`ActionWeight`] determined by the kind of [`Action`].
+///
+/// The weights are 1.0 for all kinds by default.
+///
+/// # Examples
+/// ```rust
+/// use tokyodoves::game::{ActionKindWeight, RandomAgent};
+///
+/// // Moves are chosen three times as often as puts and removes
+/// let weight = ActionKindWeight::new().with_move_weight(3.0);
+/// let agent = RandomAgent::new().with_weight(weight);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ActionKindWeight {
+    put_weight: f64,
+    move_weight: f64,
+    remove_weight: f64,
+}
+
+impl Default for ActionKindWeight {
+    fn default() -> Self {
+        Self {
+            put_weight: 1.0,
+            move_weight: 1.0,
+            remove_weight: 1.0,
+        }
+    }
+}
+
+impl ActionKindWeight {
+    /// Creates a weight with 1.0 for all kinds.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a reference to the weight of [`Action::Put`].
+    pub fn put_weight(&self) -> &f64 {
+        &self.put_weight
+    }
+
+    /// Returns a reference to the weight of [`Action::Move`].
+    pub fn move_weight(&self) -> &f64 {
+        &self.move_weight
+    }
+
+    /// Returns a reference to the weight of [`Action::Remove`].
+    pub fn remove_weight(&self) -> &f64 {
+        &self.remove_weight
+    }
+
+    /// Returns a new weight with the specified weight of [`Action::Put`].
+    pub fn with_put_weight(self, put_weight: f64) -> Self {
+        Self { put_weight, ..self }
+    }
+
+    /// Returns a new weight with the specified weight of [`Action::Move`].
+    pub fn with_move_weight(self, move_weight: f64) -> Self {
+        Self {
+            move_weight,
+            ..self
+        }
+    }
+
+    /// Returns a new weight with the specified weight of [`Action::Remove`].
+    pub fn with_remove_weight(self, remove_weight: f64) -> Self {
+        Self {
+            remove_weight,
+            ..self
+        }
+    }
+}
+
+impl ActionWeight for ActionKindWeight {
+    fn weight(&self, _board: &Board, _player: Color, action: Action) -> f64 {
+        match action {
+            Action::Put(..) => self.put_weight,
+            Action::Move(..) => self.move_weight,
+            Action::Remove(..) => self.remove_weight,
+        }
+    }
+}
+
 /// An [`Agent`] who chooses a next action at random.
-#[derive(Default)]
+///
+/// By default, all legal actions are chosen with the same probability.
+/// The probabilities can be changed by [`with_weight`](`RandomAgent::with_weight`).
 pub struct RandomAgent {
-    n: usize,
+    rng: StdRng,
+    weight: Option<Box<dyn ActionWeight + Send>>,
+}
+
+impl Default for RandomAgent {
+    fn default() -> Self {
+        Self {
+            rng: StdRng::from_entropy(),
+            weight: None,
+        }
+    }
 }
 
 impl std::fmt::Debug for RandomAgent {
@@ -575,6 +1166,9 @@ impl std::fmt::Display for RandomAgent {
 impl RandomAgent {
     /// Creates `RandomAgent` object.
     ///
+    /// The random number generator is seeded from the entropy of the system,
+    /// so that agents created by this method play different games.
+    ///
     /// # Example
     /// ```rust
     /// use tokyodoves::game::RandomAgent;
@@ -584,8 +1178,43 @@ impl RandomAgent {
         Self::default()
     }
 
-    fn update_parameter(&mut self) {
-        self.n = (33 * self.n + 31) % 65536
+    /// Returns a new agent whose random number generator is seeded by `seed`.
+    ///
+    /// Agents with the same seed choose the same actions in the same situations.
+    ///
+    /// # Example
+    /// ```rust
+    /// use tokyodoves::game::{Agent, Game, RandomAgent};
+    ///
+    /// let mut game1 = Game::new(true);
+    /// let mut game2 = Game::new(true);
+    /// RandomAgent::new().with_seed(7).play(&mut game1);
+    /// RandomAgent::new().with_seed(7).play(&mut game2);
+    /// assert_eq!(game1.board(), game2.board());
+    /// ```
+    pub fn with_seed(self, seed: u64) -> Self {
+        self.with_rng(StdRng::seed_from_u64(seed))
+    }
+
+    /// Returns a new agent with the specified random number generator.
+    pub fn with_rng(self, rng: StdRng) -> Self {
+        Self { rng, ..self }
+    }
+
+    /// Returns a new agent who chooses an action
+    /// with a probability proportional to `weight` of the action.
+    ///
+    /// If the weights of all actions are zero or some weight is invalid
+    /// (negative, infinite or NaN), an action is chosen uniformly.
+    /// See [`ActionWeight`] and [`ActionKindWeight`] for examples.
+    pub fn with_weight<W>(self, weight: W) -> Self
+    where
+        W: ActionWeight + Send + 'static,
+    {
+        Self {
+            weight: Some(Box::new(weight)),
+            ..self
+        }
     }
 }
 
@@ -603,20 +1232,42 @@ impl Agent for RandomAgent {
     /// println!("{game}");
     /// ```
     fn play(&mut self, game: &mut Game) {
-        self.update_parameter();
-        let actions = game.legal_actions();
-        let action = actions[self.n % actions.len()];
-        game.perform(action).expect("illegal situation");
+        let (board, player) = (*game.board(), *game.next_player());
+        let actions: Vec<Action> = game.legal_actions().into_iter().collect();
+        let index = match self.weight.as_ref() {
+            Some(weight) => {
+                let weights = actions
+                    .iter()
+                    .map(|action| weight.weight(&board, player, *action));
+                choose_weighted_index(&mut self.rng, weights)
+            }
+            None => None,
+        }
+        .unwrap_or_else(|| self.rng.gen_range(0..actions.len()));
+        game.perform(actions[index]).expect("illegal situation");
     }
 }
 
+/// Returns an index chosen with a probability proportional to `weights`,
+/// or `None` if the weights are not valid.
+fn choose_weighted_index<I>(rng: &mut StdRng, weights: I) -> Option<usize>
+where
+    I: IntoIterator<Item = f64>,
+{
+    WeightedIndex::new(weights)
+        .ok()
+        .map(|distribution| rng.sample(distribution))
+}
+
 /// An [`Agent`] who choses a next action based on analysis of the status.
 /// This struct is available when "analysis" feature is activated.
 #[cfg(feature = "analysis")]
 pub struct AnalystAgent {
     depth: usize,
-    n: usize,
+    time_per_move: Option<Duration>,
+    rng: StdRng,
     declare_about_to_end: bool,
+    evaluator: Box<dyn Evaluator + Send>,
 }
 
 #[cfg(feature = "analysis")]
@@ -624,6 +1275,7 @@ impl std::fmt::Debug for AnalystAgent {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("AnalystAgent")
             .field("depth", &self.depth)
+            .field("time_per_move", &self.time_per_move)
             .field("declare_about_to_end", &self.declare_about_to_end)
             .finish()
     }
@@ -654,13 +1306,125 @@ impl AnalystAgent {
     pub fn new(depth: usize, declare_about_to_end: bool) -> Self {
         Self {
             depth,
-            n: 0,
+            time_per_move: None,
+            rng: StdRng::from_entropy(),
             declare_about_to_end,
+            evaluator: Box::new(DefaultEvaluator::new()),
         }
     }
 
-    fn update_parameter(&mut self) {
-        self.n = (33 * self.n + 31) % 65536
+    /// Returns a new agent that ranks actions by the specified [`Evaluator`]
+    /// when the value of the board is not determined.
+    ///
+    /// [`DefaultEvaluator`] is used by default.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tokyodoves::analysis::DefaultEvaluator;
+    /// use tokyodoves::game::AnalystAgent;
+    ///
+    /// let evaluator = DefaultEvaluator::new().with_boss_liberty_weight(200);
+    /// let agent = AnalystAgent::new(3, true).with_evaluator(evaluator);
+    /// ```
+    pub fn with_evaluator<E>(self, evaluator: E) -> Self
+    where
+        E: Evaluator + Send + 'static,
+    {
+        Self {
+            evaluator: Box::new(evaluator),
+            ..self
+        }
+    }
+
+    /// Returns a new agent that thinks for at most `time_per_move` on each action.
+    ///
+    /// If `time_per_move` is `Some`, the agent searches deeper one turn at a time
+    /// by [`find_best_actions_with_limit`] until the time is up,
+    /// where `depth` given to [`new`](`AnalystAgent::new`) is the maximum depth.
+    /// If the value of the board is not determined,
+    /// candidates are ranked by the evaluator applied to the boards after them.
+    /// If `time_per_move` is `None`, the agent always searches `depth` turns.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::time::Duration;
+    /// use tokyodoves::game::AnalystAgent;
+    ///
+    /// let agent = AnalystAgent::new(usize::MAX, true)
+    ///     .with_time_per_move(Some(Duration::from_millis(500)));
+    /// ```
+    pub fn with_time_per_move(self, time_per_move: Option<Duration>) -> Self {
+        Self {
+            time_per_move,
+            ..self
+        }
+    }
+
+    /// Returns a new agent whose random number generator is seeded by `seed`.
+    ///
+    /// The generator is used to choose one of the best actions.
+    /// Agents with the same seed and parameters choose the same actions in the same situations
+    /// unless the time per move is set.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tokyodoves::game::AnalystAgent;
+    /// let agent = AnalystAgent::new(3, true).with_seed(7);
+    /// ```
+    pub fn with_seed(self, seed: u64) -> Self {
+        self.with_rng(StdRng::seed_from_u64(seed))
+    }
+
+    /// Returns a new agent with the specified random number generator.
+    pub fn with_rng(self, rng: StdRng) -> Self {
+        Self { rng, ..self }
+    }
+
+    /// Returns the candidates of the next action and the value of the board if determined.
+    fn candidates(
+        &self,
+        board: Board,
+        player: Color,
+        rule: GameRule,
+    ) -> (Vec<Action>, Option<BoardValue>) {
+        let Some(time_per_move) = self.time_per_move else {
+            let candidates =
+                find_best_actions_with_evaluator(board, player, self.depth, rule, &*self.evaluator)
+                    .unwrap();
+            let value = if self.declare_about_to_end {
+                evaluate_board(board, player, self.depth, rule)
+                    .unwrap()
+                    .single()
+            } else {
+                None
+            };
+            return (candidates, value);
+        };
+
+        let limit = SearchLimit::new().with_time_limit(time_per_move);
+        let (interval, candidates) =
+            find_best_actions_with_limit(board, player, self.depth, rule, &limit)
+                .unwrap()
+                .into_result();
+        let value = interval.single();
+        if value.is_some() || candidates.len() <= 1 {
+            return (candidates, value);
+        }
+        let scores: Vec<i32> = candidates
+            .iter()
+            .map(|action| {
+                let next_board = board.perform_unchecked_copied(*action);
+                -self.evaluator.evaluate(&next_board, !player)
+            })
+            .collect();
+        let max_score = scores.iter().max().copied();
+        let candidates = candidates
+            .into_iter()
+            .zip(scores)
+            .filter(|(_, score)| Some(*score) == max_score)
+            .map(|(action, _)| action)
+            .collect();
+        (candidates, value)
     }
 }
 
@@ -668,10 +1432,14 @@ impl AnalystAgent {
 impl Agent for AnalystAgent {
     /// Choses and performs an action based on analysis of the game.
     ///
-    /// It calls the [`find_best_actions`] function
+    /// It calls the [`find_best_actions_with_evaluator`] function
     /// in the [`analysis`](`crate::analysis`) module
-    /// to find candidates.
-    /// It choses and performs one of them at random.
+    /// to find candidates, which ranks actions by the evaluator of this agent
+    /// if the value of the board is not determined.
+    /// If the time per move is set by [`with_time_per_move`](`AnalystAgent::with_time_per_move`),
+    /// it calls the [`find_best_actions_with_limit`] function instead.
+    /// It choses and performs one of them at random
+    /// using the random number generator of this agent (see [`with_seed`](`AnalystAgent::with_seed`)).
     /// If the value of the board is exactly identified,
     /// it prints a message to notice that the game is about to end
     /// if it was constructed with `declare_about_to_end=true`.
@@ -687,18 +1455,14 @@ impl Agent for AnalystAgent {
     /// println!("{game}");
     /// ```
     fn play(&mut self, game: &mut Game) {
-        self.update_parameter();
         let board = *game.board();
         let player = *game.next_player();
         let rule = *game.rule();
-        let candidates = find_best_actions(board, player, self.depth, rule).unwrap();
-        let action = candidates[self.n % candidates.len()];
+        let (candidates, value) = self.candidates(board, player, rule);
+        let action = candidates[self.rng.gen_range(0..candidates.len())];
 
         if self.declare_about_to_end {
-            if let Some(val) = evaluate_board(board, player, self.depth, rule)
-                .unwrap()
-                .single()
-            {
+            if let Some(val) = value {
                 println!("!!! This game is about to end: value={val}");
             }
         }
@@ -741,6 +1505,9 @@ impl Agent for ConsoleAgent {
     ///
     /// It receives actions in SSN (standard short notation).
     /// See the documentation of [`Action`] for the definition of SSN.
+    /// If "undo" is input instead, the last two actions
+    /// (that of the opponent and that of yourself) are taken back
+    /// and it asks again.
     ///
     /// # Examples
     /// ``` ignore
@@ -753,7 +1520,7 @@ impl Agent for ConsoleAgent {
     /// println!("{game}");
     /// ```
     fn play(&mut self, game: &mut Game) {
-        let legal_actions = game.legal_actions();
+        let mut legal_actions = game.legal_actions();
 
         let mut buffer = String::new();
         let action: Action;
@@ -764,6 +1531,16 @@ impl Agent for ConsoleAgent {
                 .read_line(&mut buffer)
                 .expect("read line error");
             let ssn = buffer.trim();
+            if ssn == "undo" {
+                if game.ply() < 2 {
+                    println!("Nothing to Undo. Try Again.");
+                    continue;
+                }
+                game.jump_to(game.ply() - 2).expect("failed to undo");
+                println!("{game}");
+                legal_actions = game.legal_actions();
+                continue;
+            }
             let Ok(action_tmp) = Action::try_from_ssn(ssn, game.board()) else {
                 println!("Invalid Input. Try Again.");
                 continue;
@@ -830,6 +1607,11 @@ where
         }
     }
 
+    /// Get a reference to the game played in the arena
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
     /// Let agents play the game until the game ends.
     ///
     /// In each turn,
@@ -872,9 +1654,288 @@ where
         println!("~~~~~~ Game Finished! ~~~~~~");
         println!("Total {num_turns} turns");
         println!("{}", self.game);
+        if let GameStatus::Terminated(termination, _) = self.game.status() {
+            println!("---> Terminated by {termination:?}");
+        }
         match self.game.winner() {
             Some(player) => println!("---> {player} win!"),
             None => println!("---> Draw!"),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play_randomly(game: &mut Game, num_plies: usize) {
+        let mut n = 0;
+        for _ in 0..num_plies {
+            if !game.is_ongoing() {
+                break;
+            }
+            n = (33 * n + 31) % 65536;
+            let actions = game.legal_actions();
+            game.perform(actions[n % actions.len()]).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let mut game = Game::new(true);
+        play_randomly(&mut game, 200);
+        let ply = game.ply();
+        let board = *game.board();
+        let player = *game.next_player();
+        let status = *game.status();
+
+        let mut undone = Vec::new();
+        while let Ok(action) = game.undo() {
+            undone.push(action);
+        }
+        assert_eq!(game.ply(), 0);
+        assert_eq!(*game.board(), *game.rule().initial_board());
+        assert_eq!(*game.next_player(), *game.rule().first_player());
+        assert!(game.is_ongoing());
+
+        undone.reverse();
+        assert_eq!(undone, game.history());
+
+        let mut replayed = Game::new(true);
+        while let Ok(action) = game.redo() {
+            replayed.perform(action).unwrap();
+            assert_eq!(game.board(), replayed.board());
+            assert_eq!(game.next_player(), replayed.next_player());
+            assert_eq!(game.status(), replayed.status());
+        }
+        assert_eq!(game.ply(), ply);
+        assert_eq!(*game.board(), board);
+        assert_eq!(*game.next_player(), player);
+        assert_eq!(*game.status(), status);
+    }
+
+    #[test]
+    fn test_jump_to() {
+        let mut game = Game::new(true);
+        play_randomly(&mut game, 10);
+        let len = game.history().len();
+        assert!(game.jump_to(len + 1).is_err());
+        assert!(game.redo().is_err());
+
+        game.jump_to(3).unwrap();
+        assert_eq!(game.ply(), 3);
+        let mut replayed = Game::new(true);
+        for &action in &game.history()[..3] {
+            replayed.perform(action).unwrap();
+        }
+        assert_eq!(game.board(), replayed.board());
+
+        // Performing an action discards the actions after the current ply
+        let action = game.legal_actions()[0];
+        game.perform(action).unwrap();
+        assert_eq!(game.history().len(), 4);
+        assert_eq!(game.history()[3], action);
+        assert!(game.redo().is_err());
+
+        game.reset();
+        assert!(game.history().is_empty());
+        assert!(game.undo().is_err());
+    }
+
+    fn cyclic_actions() -> [Action; 4] {
+        use crate::prelude::{Dove, Shift};
+        use Color::*;
+        [
+            Action::Put(Red, Dove::A, Shift::new(0, 1)),
+            Action::Put(Green, Dove::A, Shift::new(0, 1)),
+            Action::Remove(Red, Dove::A),
+            Action::Remove(Green, Dove::A),
+        ]
+    }
+
+    #[test]
+    fn test_repetition_limit() {
+        let rule = GameRule::new(true)
+            .with_repetition_limit(Some(3))
+            .unwrap()
+            .with_repetition_judge(Judge::LastWins);
+        let mut game = Game::new_with_rule(rule);
+        let actions = cyclic_actions();
+        // The initial state occurs at the 0th, 4th and 8th ply
+        for ply in 0..8 {
+            assert!(game.is_ongoing());
+            game.perform(actions[ply % 4]).unwrap();
+        }
+        assert_eq!(
+            *game.status(),
+            GameStatus::Terminated(Termination::Repetition, Some(Color::Green))
+        );
+        assert_eq!(game.winner(), Some(Color::Green));
+        assert!(game.perform(actions[0]).is_err());
+
+        game.undo().unwrap();
+        assert!(game.is_ongoing());
+        game.redo().unwrap();
+        assert!(!game.is_ongoing());
+    }
+
+    #[test]
+    fn test_max_plies() {
+        let rule = GameRule::new(true).with_max_plies(Some(5)).unwrap();
+        let mut game = Game::new_with_rule(rule);
+        let actions = cyclic_actions();
+        for ply in 0..5 {
+            assert!(game.is_ongoing());
+            game.perform(actions[ply % 4]).unwrap();
+        }
+        assert_eq!(
+            *game.status(),
+            GameStatus::Terminated(Termination::MaxPlies, None)
+        );
+        assert_eq!(game.winner(), None);
+    }
+
+    #[test]
+    fn test_limit_validation() {
+        let rule = GameRule::new(true);
+        assert!(rule.with_repetition_limit(Some(0)).is_err());
+        assert!(rule.with_repetition_limit(Some(1)).is_err());
+        assert!(rule.with_repetition_limit(Some(2)).is_ok());
+        assert!(rule.with_repetition_limit(None).is_ok());
+        assert!(rule.with_max_plies(Some(0)).is_err());
+        assert!(rule.with_max_plies(Some(1)).is_ok());
+        assert!(rule.with_max_plies(None).is_ok());
+    }
+
+    #[cfg(feature = "analysis")]
+    #[test]
+    fn test_analyst_agent_with_draw_judge() {
+        let rule = GameRule::new(true)
+            .with_suicide_atk_judge(Judge::Draw)
+            .with_max_plies(Some(20))
+            .unwrap();
+        let mut game = Game::new_with_rule(rule);
+        let mut agent = AnalystAgent::new(2, false);
+        while game.is_ongoing() {
+            agent.play(&mut game);
+        }
+        assert!(game.ply() <= 20);
+    }
+
+    fn play_with(agent: &mut RandomAgent, num_plies: usize) -> Game {
+        let mut game = Game::new(true);
+        for _ in 0..num_plies {
+            if !game.is_ongoing() {
+                break;
+            }
+            agent.play(&mut game);
+        }
+        game
+    }
+
+    #[test]
+    fn test_random_agent_with_seed() {
+        let game1 = play_with(&mut RandomAgent::new().with_seed(1), 100);
+        let game2 = play_with(&mut RandomAgent::new().with_seed(1), 100);
+        assert_eq!(game1.history(), game2.history());
+
+        let game3 = play_with(&mut RandomAgent::new().with_seed(2), 100);
+        assert_ne!(game1.history(), game3.history());
+    }
+
+    #[test]
+    fn test_random_agent_with_weight() {
+        // Only moves are chosen if they are available
+        let weight = ActionKindWeight::new()
+            .with_put_weight(0.0)
+            .with_remove_weight(0.0);
+        let mut agent = RandomAgent::new().with_seed(0).with_weight(weight);
+        let mut game = Game::new(true);
+        for _ in 0..20 {
+            let has_move = game
+                .legal_actions()
+                .into_iter()
+                .any(|a| matches!(a, Action::Move(..)));
+            agent.play(&mut game);
+            let last = *game.history().last().unwrap();
+            assert_eq!(matches!(last, Action::Move(..)), has_move);
+            if !game.is_ongoing() {
+                break;
+            }
+        }
+
+        // Zero weights for all actions fall back to the uniform choice
+        let mut agent = RandomAgent::new().with_weight(|_: &Board, _: Color, _: Action| 0.0);
+        let game = play_with(&mut agent, 10);
+        assert!(!game.history().is_empty());
+    }
+
+    #[cfg(feature = "analysis")]
+    #[test]
+    fn test_analyst_agent_with_time_per_move() {
+        use crate::BoardBuilder;
+        use std::str::FromStr;
+
+        // Red wins in 5 turns, which is found long before the time runs out
+        // even on slow machines because the search stops once the value is determined
+        let board = BoardBuilder::from_str("bB;T YA").unwrap().build().unwrap();
+        let rule = GameRule::new(true).with_initial_board(board).unwrap();
+        let mut game = Game::new_with_rule(rule);
+        let mut red = AnalystAgent::new(usize::MAX, false)
+            .with_time_per_move(Some(Duration::from_secs(3600)));
+        let mut green = AnalystAgent::new(usize::MAX, false)
+            .with_time_per_move(Some(Duration::from_millis(100)));
+        for _ in 0..3 {
+            red.play(&mut game);
+            if game.is_ongoing() {
+                green.play(&mut game);
+            }
+        }
+        assert_eq!(*game.status(), GameStatus::Win(Color::Red));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        use crate::BoardBuilder;
+        use std::str::FromStr;
+        let board = BoardBuilder::from_str("bB;T YA").unwrap().build().unwrap();
+        let rule = GameRule::new(false)
+            .with_first_player(Color::Green)
+            .with_suicide_atk_judge(Judge::Draw)
+            .with_initial_board(board)
+            .unwrap()
+            .with_repetition_limit(Some(3))
+            .unwrap()
+            .with_max_plies(Some(100))
+            .unwrap()
+            .with_max_plies_judge(Judge::NextWins);
+        let json = serde_json::to_string(&rule).unwrap();
+        let rule2: GameRule = serde_json::from_str(&json).unwrap();
+        assert_eq!(format!("{rule:?}"), format!("{rule2:?}"));
+        // The initial board must not be finished
+        let mut value = serde_json::to_value(rule).unwrap();
+        value["initial_board"] = "bBAh;T;M;H".into();
+        assert!(serde_json::from_value::<GameRule>(value.clone()).is_err());
+        // The limits must be valid
+        value["initial_board"] = serde_json::to_value(board).unwrap();
+        value["repetition_limit"] = 1.into();
+        assert!(serde_json::from_value::<GameRule>(value.clone()).is_err());
+        value["repetition_limit"] = 2.into();
+        value["max_plies"] = 0.into();
+        assert!(serde_json::from_value::<GameRule>(value.clone()).is_err());
+        value["max_plies"] = 1.into();
+        assert!(serde_json::from_value::<GameRule>(value).is_ok());
+
+        let statuses = [
+            GameStatus::Ongoing,
+            GameStatus::Win(Color::Red),
+            GameStatus::Draw,
+            GameStatus::Terminated(Termination::Repetition, None),
+        ];
+        for status in statuses {
+            let json = serde_json::to_string(&status).unwrap();
+            assert_eq!(serde_json::from_str::<GameStatus>(&json).unwrap(), status);
+        }
+    }
+}
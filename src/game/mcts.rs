@@ -0,0 +1,637 @@
+use std::time::{Duration, Instant};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+#[cfg(feature = "analysis")]
+use crate::analysis::{evaluate_board, find_best_actions};
+use crate::{
+    game::{Agent, Game, GameRule, GameStatus, Termination},
+    prelude::{Action, ActionContainer, Board, Color, SurroundedStatus},
+};
+
+/// An [`Agent`] who chooses a next action by Monte Carlo tree search (MCTS).
+///
+/// Each iteration of the search descends the tree by the UCT formula,
+/// adds a new node and plays a random game (playout) from it
+/// by [`Board::legal_actions`] until a boss is surrounded
+/// ([`Board::surrounded_status`]).
+/// The action visited most often at the root is performed.
+///
+/// The search finishes when the number of iterations reaches the limit
+/// ([`with_iterations`](`MctsAgent::with_iterations`))
+/// or the time budget is exhausted ([`with_time_budget`](`MctsAgent::with_time_budget`)),
+/// whichever comes first.
+/// Playouts longer than [`max_playout_plies`](`MctsAgent::with_max_playout_plies`)
+/// are treated as draws in the search.
+/// Games in the search are also finished by the limitations of [`GameRule`]
+/// ([`repetition_limit`](`GameRule::repetition_limit`) and [`max_plies`](`GameRule::max_plies`))
+/// counting the states of the game before the search.
+///
+/// When "analysis" feature is activated, the agent can also consult
+/// the exhaustive search of the [`analysis`](`crate::analysis`) module
+/// by [`with_mate_search_depth`](`MctsAgent::with_mate_search_depth`).
+///
+/// # Examples
+/// ```rust
+/// use tokyodoves::game::{Agent, Game, MctsAgent};
+///
+/// let mut agent = MctsAgent::new().with_iterations(200).with_seed(42);
+/// let mut game = Game::new(true);
+/// agent.play(&mut game);
+/// assert_eq!(game.ply(), 1);
+/// ```
+pub struct MctsAgent {
+    iterations: usize,
+    time_budget: Option<Duration>,
+    exploration: f64,
+    max_playout_plies: usize,
+    #[cfg(feature = "analysis")]
+    mate_search_depth: Option<usize>,
+    rng: StdRng,
+}
+
+impl std::fmt::Debug for MctsAgent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut debug = f.debug_struct("MctsAgent");
+        debug
+            .field("iterations", &self.iterations)
+            .field("time_budget", &self.time_budget)
+            .field("exploration", &self.exploration)
+            .field("max_playout_plies", &self.max_playout_plies);
+        #[cfg(feature = "analysis")]
+        debug.field("mate_search_depth", &self.mate_search_depth);
+        debug.finish()
+    }
+}
+
+impl std::fmt::Display for MctsAgent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "MctsAgent")
+    }
+}
+
+impl Default for MctsAgent {
+    fn default() -> Self {
+        Self {
+            iterations: 1000,
+            time_budget: None,
+            exploration: std::f64::consts::SQRT_2,
+            max_playout_plies: 200,
+            #[cfg(feature = "analysis")]
+            mate_search_depth: None,
+            rng: StdRng::from_entropy(),
+        }
+    }
+}
+
+impl MctsAgent {
+    /// Creates an [`MctsAgent`] object with default parameters.
+    ///
+    /// The default parameters are as below:
+    /// - iterations: 1000
+    /// - time budget: `None` (unlimited)
+    /// - exploration constant: √2
+    /// - max playout plies: 200
+    /// - mate search depth: `None` (disabled)
+    ///
+    /// The random number generator is seeded from the entropy of the system.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tokyodoves::game::MctsAgent;
+    /// let agent = MctsAgent::new();
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a reference to the maximum number of iterations per action.
+    pub fn iterations(&self) -> &usize {
+        &self.iterations
+    }
+
+    /// Returns a reference to the time budget per action.
+    pub fn time_budget(&self) -> &Option<Duration> {
+        &self.time_budget
+    }
+
+    /// Returns a reference to the exploration constant of UCT.
+    pub fn exploration(&self) -> &f64 {
+        &self.exploration
+    }
+
+    /// Returns a reference to the maximum number of plies in a playout.
+    pub fn max_playout_plies(&self) -> &usize {
+        &self.max_playout_plies
+    }
+
+    /// Returns a new agent with the specified maximum number of iterations per action.
+    ///
+    /// At least one iteration is performed.
+    pub fn with_iterations(self, iterations: usize) -> Self {
+        Self { iterations, ..self }
+    }
+
+    /// Returns a new agent with the specified time budget per action.
+    ///
+    /// If `time_budget` is `None`, the search is limited only by the number of iterations.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::time::Duration;
+    /// use tokyodoves::game::MctsAgent;
+    ///
+    /// let agent = MctsAgent::new()
+    ///     .with_iterations(usize::MAX)
+    ///     .with_time_budget(Some(Duration::from_millis(500)));
+    /// ```
+    pub fn with_time_budget(self, time_budget: Option<Duration>) -> Self {
+        Self {
+            time_budget,
+            ..self
+        }
+    }
+
+    /// Returns a new agent with the specified exploration constant of UCT.
+    ///
+    /// The greater the constant is, the more the search explores less visited actions.
+    pub fn with_exploration(self, exploration: f64) -> Self {
+        Self {
+            exploration,
+            ..self
+        }
+    }
+
+    /// Returns a new agent with the specified maximum number of plies in a playout.
+    pub fn with_max_playout_plies(self, max_playout_plies: usize) -> Self {
+        Self {
+            max_playout_plies,
+            ..self
+        }
+    }
+
+    /// Returns a new agent whose random number generator is seeded by `seed`.
+    ///
+    /// Agents with the same seed and parameters perform the same actions
+    /// if the search is not limited by the time budget.
+    pub fn with_seed(self, seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            ..self
+        }
+    }
+
+    /// Returns a new agent that searches `mate_search_depth` turns exhaustively
+    /// at each node added to the tree.
+    /// This method is available when "analysis" feature is activated.
+    ///
+    /// Nodes whose values are determined by [`evaluate_board`]
+    /// are treated as finished games, and an action is performed immediately
+    /// if the value of the current board turns out to be a win.
+    /// If `mate_search_depth` is `None`, the exhaustive search is disabled.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tokyodoves::game::MctsAgent;
+    /// let agent = MctsAgent::new().with_mate_search_depth(Some(3));
+    /// ```
+    #[cfg(feature = "analysis")]
+    pub fn with_mate_search_depth(self, mate_search_depth: Option<usize>) -> Self {
+        Self {
+            mate_search_depth,
+            ..self
+        }
+    }
+
+    /// Returns the action to be performed.
+    fn search(&mut self, game: &Game) -> Action {
+        let (board, player, rule) = (*game.board(), *game.next_player(), *game.rule());
+        #[cfg(feature = "analysis")]
+        if let Some(depth) = self.mate_search_depth {
+            let is_win = evaluate_board(board, player, depth, rule)
+                .is_ok_and(|interval| interval.single().is_some_and(|value| value.is_win()));
+            if is_win {
+                if let Some(action) = find_best_actions(board, player, depth, rule)
+                    .ok()
+                    .and_then(|actions| actions.first().copied())
+                {
+                    return action;
+                }
+            }
+        }
+
+        let started = Instant::now();
+        let states = game.states[..=game.ply]
+            .iter()
+            .map(|state| (state.key, state.player))
+            .collect();
+        let mut tree = Tree::new(board, player, rule, states);
+        for i in 0..self.iterations.max(1) {
+            if i > 0
+                && self
+                    .time_budget
+                    .is_some_and(|budget| started.elapsed() >= budget)
+            {
+                break;
+            }
+            self.iterate(&mut tree);
+        }
+        tree.most_visited_action()
+    }
+
+    /// Performs one iteration: selection, expansion, playout and backpropagation.
+    fn iterate(&mut self, tree: &mut Tree) {
+        let mut index = tree.select(self.exploration);
+        let mut states = tree.states_to(index);
+        if tree.nodes[index].status == GameStatus::Ongoing && !tree.nodes[index].untried.is_empty()
+        {
+            index = self.expand(tree, index, &mut states);
+        }
+        let node = &tree.nodes[index];
+        let status = match node.status {
+            GameStatus::Ongoing => self.playout(node.board, node.player, &tree.rule, states),
+            status => status,
+        };
+        tree.backpropagate(index, status);
+    }
+
+    /// Adds a child of the node at `index` by an action chosen at random
+    /// and returns the index of the child.
+    ///
+    /// `states` are the states from the beginning of the game to the node at `index`
+    /// and the state of the child is pushed to them.
+    fn expand(&mut self, tree: &mut Tree, index: usize, states: &mut Vec<(u64, Color)>) -> usize {
+        let untried = &mut tree.nodes[index].untried;
+        let action = untried.swap_remove(self.rng.gen_range(0..untried.len()));
+        let (board, player) = (tree.nodes[index].board, tree.nodes[index].player);
+        let next_board = board.perform_unchecked_copied(action);
+        states.push((next_board.to_translation_invariant_u64(), !player));
+        #[allow(unused_mut)]
+        let mut status = status_after(&next_board, player, &tree.rule, states);
+
+        #[cfg(feature = "analysis")]
+        if let (Some(depth), GameStatus::Ongoing) = (self.mate_search_depth, status) {
+            if let Some(value) = evaluate_board(next_board, !player, depth, tree.rule)
+                .ok()
+                .and_then(|interval| interval.single())
+            {
+                if value.is_win() {
+                    status = GameStatus::Win(!player);
+                } else if value.is_lose() {
+                    status = GameStatus::Win(player);
+                }
+            }
+        }
+
+        tree.add_child(index, action, next_board, status)
+    }
+
+    /// Plays a game at random and returns the status at the end.
+    ///
+    /// `states` are the states from the beginning of the game to `board`.
+    fn playout(
+        &mut self,
+        mut board: Board,
+        mut player: Color,
+        rule: &GameRule,
+        mut states: Vec<(u64, Color)>,
+    ) -> GameStatus {
+        let is_remove_accepted = *rule.is_remove_accepted();
+        for _ in 0..self.max_playout_plies {
+            let actions = board.legal_actions(player, true, true, is_remove_accepted);
+            if actions.is_empty() {
+                break;
+            }
+            let action = actions[self.rng.gen_range(0..actions.len())];
+            board.perform_unchecked(action);
+            states.push((board.to_translation_invariant_u64(), !player));
+            let status = status_after(&board, player, rule, &states);
+            if status != GameStatus::Ongoing {
+                return status;
+            }
+            player = !player;
+        }
+        GameStatus::Draw
+    }
+}
+
+impl Agent for MctsAgent {
+    /// Performs the action visited most often by Monte Carlo tree search.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tokyodoves::game::{Agent, Game, MctsAgent};
+    ///
+    /// let mut agent = MctsAgent::new().with_iterations(100);
+    /// let mut game = Game::new(true);
+    /// println!("{game}");
+    /// agent.play(&mut game);
+    /// println!("{game}");
+    /// ```
+    fn play(&mut self, game: &mut Game) {
+        let action = self.search(game);
+        game.perform(action).expect("illegal situation");
+    }
+}
+
+/// Returns the status of the game after `last_player` performed an action resulting in `board`
+/// in the same way as [`Game::perform`],
+/// where `states` are the keys and next players of the states
+/// from the beginning of the game to `board`.
+fn status_after(
+    board: &Board,
+    last_player: Color,
+    rule: &GameRule,
+    states: &[(u64, Color)],
+) -> GameStatus {
+    match board.surrounded_status() {
+        SurroundedStatus::Both => {
+            return match rule.suicide_atk_judge().winner(last_player) {
+                Some(winner) => GameStatus::Win(winner),
+                None => GameStatus::Draw,
+            }
+        }
+        SurroundedStatus::OneSide(player) => return GameStatus::Win(!player),
+        SurroundedStatus::None => (),
+    }
+
+    let current = states.last().expect("states must contain the current one");
+    let is_repeated = rule
+        .repetition_limit()
+        .is_some_and(|limit| states.iter().filter(|state| *state == current).count() >= limit);
+    if is_repeated {
+        let winner = rule.repetition_judge().winner(last_player);
+        GameStatus::Terminated(Termination::Repetition, winner)
+    } else if rule.max_plies().is_some_and(|max| states.len() > max) {
+        let winner = rule.max_plies_judge().winner(last_player);
+        GameStatus::Terminated(Termination::MaxPlies, winner)
+    } else {
+        GameStatus::Ongoing
+    }
+}
+
+/// A node of the search tree
+struct Node {
+    board: Board,
+    /// The next player on `board`
+    player: Color,
+    /// The key of `board` to detect repetition of states
+    key: u64,
+    /// The action leading to this node from the parent
+    action: Option<Action>,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    untried: Vec<Action>,
+    status: GameStatus,
+    visits: u32,
+    /// The sum of rewards for the player who performed `action`
+    reward: f64,
+}
+
+/// A search tree whose nodes are stored in a vector
+struct Tree {
+    rule: GameRule,
+    nodes: Vec<Node>,
+    /// The keys and next players of the states from the beginning of the game to the root
+    states: Vec<(u64, Color)>,
+}
+
+impl Tree {
+    fn new(board: Board, player: Color, rule: GameRule, states: Vec<(u64, Color)>) -> Self {
+        let mut tree = Self {
+            rule,
+            nodes: Vec::new(),
+            states,
+        };
+        tree.push(None, None, board, player, GameStatus::Ongoing);
+        tree
+    }
+
+    fn push(
+        &mut self,
+        parent: Option<usize>,
+        action: Option<Action>,
+        board: Board,
+        player: Color,
+        status: GameStatus,
+    ) -> usize {
+        let untried = if status == GameStatus::Ongoing {
+            board
+                .legal_actions(player, true, true, *self.rule.is_remove_accepted())
+                .into_iter()
+                .collect()
+        } else {
+            Vec::new()
+        };
+        self.nodes.push(Node {
+            board,
+            player,
+            key: board.to_translation_invariant_u64(),
+            action,
+            parent,
+            children: Vec::new(),
+            untried,
+            status,
+            visits: 0,
+            reward: 0.0,
+        });
+        self.nodes.len() - 1
+    }
+
+    fn add_child(
+        &mut self,
+        parent: usize,
+        action: Action,
+        board: Board,
+        status: GameStatus,
+    ) -> usize {
+        let player = !self.nodes[parent].player;
+        let child = self.push(Some(parent), Some(action), board, player, status);
+        self.nodes[parent].children.push(child);
+        child
+    }
+
+    /// Descends the tree from the root by UCT until reaching a node
+    /// which is finished or has an untried action.
+    fn select(&self, exploration: f64) -> usize {
+        let mut index = 0;
+        loop {
+            let node = &self.nodes[index];
+            if node.status != GameStatus::Ongoing
+                || !node.untried.is_empty()
+                || node.children.is_empty()
+            {
+                return index;
+            }
+            let log_visits = (node.visits.max(1) as f64).ln();
+            index = *node
+                .children
+                .iter()
+                .max_by(|x, y| {
+                    let ucb = |i: usize| {
+                        let child = &self.nodes[i];
+                        let visits = child.visits.max(1) as f64;
+                        child.reward / visits + exploration * (log_visits / visits).sqrt()
+                    };
+                    ucb(**x).total_cmp(&ucb(**y))
+                })
+                .unwrap();
+        }
+    }
+
+    /// Returns the keys and next players of the states
+    /// from the beginning of the game to the node at `index`.
+    fn states_to(&self, index: usize) -> Vec<(u64, Color)> {
+        let mut path = Vec::new();
+        let mut current = index;
+        // The state of the root is already contained in `self.states`
+        while let Some(parent) = self.nodes[current].parent {
+            let node = &self.nodes[current];
+            path.push((node.key, node.player));
+            current = parent;
+        }
+        let mut states = self.states.clone();
+        states.extend(path.into_iter().rev());
+        states
+    }
+
+    /// Updates statistics of the nodes from `index` to the root.
+    fn backpropagate(&mut self, index: usize, status: GameStatus) {
+        let winner = match status {
+            GameStatus::Win(winner) | GameStatus::Terminated(_, Some(winner)) => Some(winner),
+            _ => None,
+        };
+        let mut current = Some(index);
+        while let Some(index) = current {
+            let node = &mut self.nodes[index];
+            node.visits += 1;
+            node.reward += match winner {
+                Some(winner) if winner == node.player => 0.0,
+                Some(_) => 1.0,
+                None => 0.5,
+            };
+            current = node.parent;
+        }
+    }
+
+    fn most_visited_action(&self) -> Action {
+        let root = &self.nodes[0];
+        root.children
+            .iter()
+            .map(|&i| &self.nodes[i])
+            .max_by_key(|child| child.visits)
+            .and_then(|child| child.action)
+            .or_else(|| root.untried.first().copied())
+            .expect("no legal action")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BoardBuilder;
+    use std::str::FromStr;
+
+    fn game_from(s: &str) -> Game {
+        let board = BoardBuilder::from_str(s).unwrap().build().unwrap();
+        Game::new_with_rule(GameRule::new(true).with_initial_board(board).unwrap())
+    }
+
+    #[test]
+    fn test_mcts_finds_win_in_one() {
+        let mut game = game_from(" Byt;H Ma;A m ;  Yb");
+        let mut agent = MctsAgent::new().with_iterations(300).with_seed(0);
+        agent.play(&mut game);
+        assert_eq!(*game.status(), GameStatus::Win(Color::Red));
+    }
+
+    #[test]
+    fn test_mcts_is_reproducible() {
+        let play = |seed: u64| {
+            let mut game = Game::new(true);
+            let mut red = MctsAgent::new().with_iterations(50).with_seed(seed);
+            let mut green = MctsAgent::new().with_iterations(50).with_seed(seed + 1);
+            for _ in 0..3 {
+                red.play(&mut game);
+                green.play(&mut game);
+            }
+            game.to_record()
+        };
+        assert_eq!(play(1), play(1));
+    }
+
+    #[test]
+    fn test_mcts_with_repetition() {
+        // Green wins by moving back to the state after the second action
+        let record = [
+            "remove: true",
+            "first: Red",
+            "judge: NextWins",
+            "board: b---;B---;----;----",
+            "repetition_limit: 2",
+            "repetition_judge: LastWins",
+            "max_plies: none",
+            "max_plies_judge: Draw",
+            "moves:",
+            "+AW1",
+            "+aN1E1",
+            "AN1W1",
+            "aN2",
+            "AW1",
+        ]
+        .join("\n");
+        let mut game = Game::from_record(&record).unwrap();
+        let mut agent = MctsAgent::new().with_iterations(300).with_seed(0);
+        agent.play(&mut game);
+        assert_eq!(
+            *game.status(),
+            GameStatus::Terminated(Termination::Repetition, Some(Color::Green))
+        );
+    }
+
+    #[test]
+    fn test_status_after_coincides_with_game() {
+        let rule = GameRule::new(true)
+            .with_repetition_limit(Some(2))
+            .unwrap()
+            .with_max_plies(Some(30))
+            .unwrap()
+            .with_max_plies_judge(crate::game::Judge::LastWins);
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..100 {
+            let mut game = Game::new_with_rule(rule);
+            while game.is_ongoing() {
+                let player = *game.next_player();
+                let actions = game.legal_actions();
+                game.perform(actions[rng.gen_range(0..actions.len())])
+                    .unwrap();
+                let states: Vec<_> = game.states[..=game.ply]
+                    .iter()
+                    .map(|state| (state.key, state.player))
+                    .collect();
+                let status = status_after(game.board(), player, &rule, &states);
+                assert_eq!(status, *game.status());
+            }
+        }
+    }
+
+    #[cfg(feature = "analysis")]
+    #[test]
+    fn test_mcts_with_mate_search() {
+        // Red wins in 5 turns
+        let mut game = game_from("bB;T YA");
+        let mut red = MctsAgent::new()
+            .with_iterations(10)
+            .with_seed(0)
+            .with_mate_search_depth(Some(5));
+        let mut green = MctsAgent::new().with_iterations(10).with_seed(1);
+        while game.is_ongoing() {
+            red.play(&mut game);
+            if game.is_ongoing() {
+                green.play(&mut game);
+            }
+        }
+        assert_eq!(*game.status(), GameStatus::Win(Color::Red));
+        assert!(game.ply() <= 5);
+    }
+}
@@ -0,0 +1,319 @@
+use std::str::FromStr;
+
+use crate::{
+    error,
+    game::{Game, GameRule, Judge},
+    prelude::{Board, BoardBuilder, Color},
+};
+
+/// A position of a game: a [`Board`], the player to move next
+/// and optionally a part of [`GameRule`] that affects the analysis of the board.
+///
+/// A position is written in a single line (like FEN in chess) as
+/// ```text
+/// <placement> <next player> [<remove> <judge>]
+/// ```
+/// where the fields are separated by whitespaces:
+/// - `placement`<br>
+///     ... the board in the form of [`Board::to_simple_string`] with `'-'` and `";"`,
+///     e.g. `b---;B---;----;----`
+///     (any form accepted by [`BoardBuilder::from_str`] without whitespaces is also accepted)
+/// - `next player`<br>
+///     ... `r` (red) or `g` (green)
+/// - `remove`<br>
+///     ... `+` if [`Action::Remove`](`crate::Action::Remove`) is accepted and `-` otherwise
+/// - `judge`<br>
+///     ... judgement when both bosses are surrounded:
+///     `L` ([`Judge::LastWins`]), `N` ([`Judge::NextWins`]) or `D` ([`Judge::Draw`])
+///
+/// The last two fields (the rule section) are optional but must appear together.
+/// [`FromStr`] and [`Display`](`std::fmt::Display`) convert a position
+/// from and into this notation without loss of information.
+///
+/// # Examples
+/// ```rust
+/// use std::str::FromStr;
+/// use tokyodoves::{Board, Color};
+/// use tokyodoves::game::{Game, Judge, Position};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let position = Position::from_str("b---;B---;----;---- g + L")?;
+/// assert_eq!(*position.board(), Board::new());
+/// assert_eq!(*position.next_player(), Color::Green);
+/// assert_eq!(position.is_remove_accepted(), Some(true));
+/// assert_eq!(position.suicide_atk_judge(), Some(Judge::LastWins));
+/// assert_eq!(position.to_string(), "b---;B---;----;---- g + L");
+///
+/// let game = Game::from_position(&position)?;
+/// assert_eq!(*game.next_player(), Color::Green);
+/// assert_eq!(game.position(), position);
+///
+/// // The rule section can be omitted
+/// let position = Position::from_str("b;B r")?;
+/// assert_eq!(position.is_remove_accepted(), None);
+/// assert_eq!(position.to_string(), "b---;B---;----;---- r");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Position {
+    board: Board,
+    next_player: Color,
+    rule: Option<(bool, Judge)>,
+}
+
+impl std::fmt::Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            self.board.to_simple_string('-', ";"),
+            format_color(self.next_player)
+        )?;
+        if let Some((is_remove_accepted, judge)) = self.rule {
+            let remove = if is_remove_accepted { '+' } else { '-' };
+            write!(f, " {remove} {}", format_judge(judge))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Position {
+    type Err = error::Error;
+
+    /// Creates [`Position`] from the notation described in the documentation of [`Position`].
+    ///
+    /// # Errors
+    /// It returns:
+    /// - `Err(error::PositionErrorKind::InvalidFormat(..).into())`
+    ///     if the number of fields is neither 2 nor 4.
+    /// - `Err(error::PositionErrorKind::InvalidValue(..).into())`
+    ///     if some field except the placement is invalid.
+    /// - `Err(error::Error::BoardError(..).into())` if the placement is invalid.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use error::PositionErrorKind::*;
+
+        let fields: Vec<&str> = s.split_whitespace().collect();
+        let (placement, player, rule) = match fields[..] {
+            [placement, player] => (placement, player, None),
+            [placement, player, remove, judge] => (placement, player, Some((remove, judge))),
+            _ => return Err(InvalidFormat(s.to_string()).into()),
+        };
+
+        let board = BoardBuilder::from_str(placement)?.build()?;
+        let next_player = parse_color(player).ok_or_else(|| InvalidValue(player.to_string()))?;
+        let rule = match rule {
+            Some((remove, judge)) => {
+                let is_remove_accepted = match remove {
+                    "+" => true,
+                    "-" => false,
+                    _ => return Err(InvalidValue(remove.to_string()).into()),
+                };
+                let judge = parse_judge(judge).ok_or_else(|| InvalidValue(judge.to_string()))?;
+                Some((is_remove_accepted, judge))
+            }
+            None => None,
+        };
+        Ok(Self {
+            board,
+            next_player,
+            rule,
+        })
+    }
+}
+
+impl Position {
+    /// Creates a position without the rule section.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tokyodoves::{Board, Color};
+    /// use tokyodoves::game::Position;
+    ///
+    /// let position = Position::new(Board::new(), Color::Red);
+    /// assert_eq!(position.to_string(), "b---;B---;----;---- r");
+    /// ```
+    pub fn new(board: Board, next_player: Color) -> Self {
+        Self {
+            board,
+            next_player,
+            rule: None,
+        }
+    }
+
+    /// Returns a reference to the board.
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Returns a reference to the player to move next.
+    pub fn next_player(&self) -> &Color {
+        &self.next_player
+    }
+
+    /// Returns whether [`Action::Remove`](`crate::Action::Remove`) is accepted,
+    /// or `None` if the position has no rule section.
+    pub fn is_remove_accepted(&self) -> Option<bool> {
+        self.rule.map(|(is_remove_accepted, _)| is_remove_accepted)
+    }
+
+    /// Returns the judgement when both bosses are surrounded,
+    /// or `None` if the position has no rule section.
+    pub fn suicide_atk_judge(&self) -> Option<Judge> {
+        self.rule.map(|(_, judge)| judge)
+    }
+
+    /// Returns a new position with the rule section taken from `rule`.
+    ///
+    /// Only [`is_remove_accepted`](`GameRule::is_remove_accepted`)
+    /// and [`suicide_atk_judge`](`GameRule::suicide_atk_judge`) are kept.
+    pub fn with_rule(self, rule: &GameRule) -> Self {
+        Self {
+            rule: Some((*rule.is_remove_accepted(), *rule.suicide_atk_judge())),
+            ..self
+        }
+    }
+
+    /// Returns a new position without the rule section.
+    pub fn without_rule(self) -> Self {
+        Self { rule: None, ..self }
+    }
+
+    /// Creates [`GameRule`] starting from this position.
+    ///
+    /// The initial board and the first player are those of this position.
+    /// The rule section overrides the corresponding fields of `GameRule::default()`
+    /// if it exists.
+    ///
+    /// # Errors
+    /// Returns `Err` if the board is that of finished game
+    /// in the same way as [`GameRule::with_initial_board`].
+    pub fn to_rule(&self) -> Result<GameRule, error::Error> {
+        let mut rule = GameRule::default();
+        if let Some((is_remove_accepted, judge)) = self.rule {
+            rule = rule
+                .with_is_remove_accepted(is_remove_accepted)
+                .with_suicide_atk_judge(judge);
+        }
+        Ok(rule
+            .with_initial_board(self.board)?
+            .with_first_player(self.next_player))
+    }
+}
+
+impl Game {
+    /// Creates [`Game`] starting from `position`.
+    ///
+    /// The rule of the game is given by [`Position::to_rule`].
+    ///
+    /// # Errors
+    /// Returns `Err` if the board of `position` is that of finished game.
+    pub fn from_position(position: &Position) -> Result<Self, error::Error> {
+        Ok(Game::new_with_rule(position.to_rule()?))
+    }
+
+    /// Returns the current [`Position`] of the game including the rule section.
+    pub fn position(&self) -> Position {
+        Position::new(*self.board(), *self.next_player()).with_rule(self.rule())
+    }
+}
+
+fn format_color(color: Color) -> char {
+    match color {
+        Color::Red => 'r',
+        Color::Green => 'g',
+    }
+}
+
+fn parse_color(s: &str) -> Option<Color> {
+    match s {
+        "r" => Some(Color::Red),
+        "g" => Some(Color::Green),
+        _ => None,
+    }
+}
+
+fn format_judge(judge: Judge) -> char {
+    match judge {
+        Judge::LastWins => 'L',
+        Judge::NextWins => 'N',
+        Judge::Draw => 'D',
+    }
+}
+
+fn parse_judge(s: &str) -> Option<Judge> {
+    match s {
+        "L" => Some(Judge::LastWins),
+        "N" => Some(Judge::NextWins),
+        "D" => Some(Judge::Draw),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::ActionContainer;
+
+    #[test]
+    fn test_position_round_trip() {
+        let rules = [
+            GameRule::new(true),
+            GameRule::new(false).with_suicide_atk_judge(Judge::LastWins),
+            GameRule::new(true).with_suicide_atk_judge(Judge::Draw),
+        ];
+        for rule in rules {
+            let mut game = Game::new_with_rule(rule);
+            let mut n = 0;
+            while game.is_ongoing() && game.ply() < 100 {
+                let position = game.position();
+                let parsed = Position::from_str(&position.to_string()).unwrap();
+                assert_eq!(parsed, position);
+                assert_eq!(parsed.to_string(), position.to_string());
+
+                let restored = Game::from_position(&parsed).unwrap();
+                assert_eq!(restored.board(), game.board());
+                assert_eq!(restored.next_player(), game.next_player());
+                assert_eq!(
+                    restored.rule().is_remove_accepted(),
+                    rule.is_remove_accepted()
+                );
+                assert_eq!(
+                    restored.rule().suicide_atk_judge(),
+                    rule.suicide_atk_judge()
+                );
+
+                let without_rule = position.without_rule();
+                assert_eq!(
+                    Position::from_str(&without_rule.to_string()).unwrap(),
+                    without_rule
+                );
+
+                n = (33 * n + 31) % 65536;
+                let actions = game.legal_actions();
+                game.perform(actions[n % actions.len()]).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_invalid_position() {
+        for s in [
+            "",
+            "b;B",
+            "b;B r +",
+            "b;B r + N extra",
+            "b;B x",
+            "b;B r * N",
+            "b;B r + X",
+            "b;BB r",
+        ] {
+            assert!(Position::from_str(s).is_err(), "{s:?}");
+        }
+
+        // A finished board is a valid position but cannot start a game
+        let position = Position::from_str("bBh;Aa-m;--M;-H-Y g").unwrap();
+        assert!(Game::from_position(&position).is_err());
+    }
+}
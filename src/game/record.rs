@@ -0,0 +1,364 @@
+use std::str::FromStr;
+
+use crate::{
+    error,
+    game::{Game, GameRule, Judge},
+    prelude::{Action, BoardBuilder, Color},
+};
+
+const KEY_REMOVE: &str = "remove";
+const KEY_FIRST: &str = "first";
+const KEY_JUDGE: &str = "judge";
+const KEY_BOARD: &str = "board";
+const KEY_REPETITION_LIMIT: &str = "repetition_limit";
+const KEY_REPETITION_JUDGE: &str = "repetition_judge";
+const KEY_MAX_PLIES: &str = "max_plies";
+const KEY_MAX_PLIES_JUDGE: &str = "max_plies_judge";
+const KEY_MOVES: &str = "moves";
+
+impl Game {
+    /// Converts the game into a record (kifu) in plain text.
+    ///
+    /// A record consists of a header that describes [`GameRule`]
+    /// and a list of actions in SSN, for example,
+    /// ```text
+    /// remove: true
+    /// first: Red
+    /// judge: NextWins
+    /// board: b---;B---;----;----
+    /// repetition_limit: 3
+    /// repetition_judge: Draw
+    /// max_plies: none
+    /// max_plies_judge: Draw
+    /// moves:
+    /// +AS1
+    /// +aN1
+    /// ```
+    /// Each line in the header is in "key: value" form:
+    /// - `remove`<br>
+    ///     ... whether [`Action::Remove`] is accepted (`true` or `false`)
+    /// - `first`<br>
+    ///     ... the player who moves first (`Red` or `Green`)
+    /// - `judge`<br>
+    ///     ... judgement when both bosses are surrounded (`LastWins`, `NextWins` or `Draw`)
+    /// - `board`<br>
+    ///     ... the initial board in the form accepted by
+    ///     [`BoardBuilder::from_str`](`crate::BoardBuilder::from_str`)
+    /// - `repetition_limit`<br>
+    ///     ... the number of occurrences of the same state that finishes the game
+    ///     (a positive integer or `none`)
+    /// - `repetition_judge`<br>
+    ///     ... judgement when the game is finished by repetition
+    /// - `max_plies`<br>
+    ///     ... the number of actions that finishes the game (a positive integer or `none`)
+    /// - `max_plies_judge`<br>
+    ///     ... judgement when the game is finished by the number of actions
+    ///
+    /// The actions are listed after the line `moves:`.
+    /// Only actions performed until the current [`ply`](`Self::ply`) are recorded,
+    /// i.e., actions taken back by the [`undo`](`Self::undo`) method are not included.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tokyodoves::{Action, Color, Dove, Shift};
+    /// use tokyodoves::game::Game;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut game = Game::new(true);
+    /// game.perform(Action::Put(Color::Red, Dove::A, Shift::new(1, 0)))?;
+    /// let record = game.to_record();
+    /// assert_eq!(
+    ///     record,
+    ///     [
+    ///         "remove: true",
+    ///         "first: Red",
+    ///         "judge: NextWins",
+    ///         "board: b---;B---;----;----",
+    ///         "repetition_limit: none",
+    ///         "repetition_judge: Draw",
+    ///         "max_plies: none",
+    ///         "max_plies_judge: Draw",
+    ///         "moves:",
+    ///         "+AS1\n",
+    ///     ].join("\n")
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_record(&self) -> String {
+        let rule = self.rule();
+        let mut record = String::new();
+        record.push_str(&format!("{KEY_REMOVE}: {}\n", rule.is_remove_accepted()));
+        record.push_str(&format!("{KEY_FIRST}: {}\n", rule.first_player()));
+        record.push_str(&format!("{KEY_JUDGE}: {:?}\n", rule.suicide_atk_judge()));
+        record.push_str(&format!(
+            "{KEY_BOARD}: {}\n",
+            rule.initial_board().to_simple_string('-', ";")
+        ));
+        record.push_str(&format!(
+            "{KEY_REPETITION_LIMIT}: {}\n",
+            format_limit(rule.repetition_limit())
+        ));
+        record.push_str(&format!(
+            "{KEY_REPETITION_JUDGE}: {:?}\n",
+            rule.repetition_judge()
+        ));
+        record.push_str(&format!(
+            "{KEY_MAX_PLIES}: {}\n",
+            format_limit(rule.max_plies())
+        ));
+        record.push_str(&format!(
+            "{KEY_MAX_PLIES_JUDGE}: {:?}\n",
+            rule.max_plies_judge()
+        ));
+        record.push_str(&format!("{KEY_MOVES}:\n"));
+
+        for (state, action) in self.states.iter().zip(&self.history[..self.ply]) {
+            let ssn = action
+                .try_into_ssn(&state.board)
+                .expect("recorded action must be legal");
+            record.push_str(&ssn);
+            record.push('\n');
+        }
+        record
+    }
+
+    /// Creates [`Game`] from a record (kifu) in plain text.
+    ///
+    /// See the documentation of the [`to_record`](`Self::to_record`) method
+    /// for the format of records.
+    /// Missing keys in the header follow the default value of [`GameRule`].
+    /// Empty lines and lines starting with "#" are ignored.
+    /// The actions after `moves:` may be separated by any whitespaces.
+    ///
+    /// All actions are validated by performing them in order,
+    /// and the resulting game keeps them in its [`history`](`Self::history`).
+    ///
+    /// # Errors
+    /// It returns:
+    /// - `Err(error::RecordErrorKind::UnknownKey(..).into())`
+    ///     if the header contains an unknown key.
+    /// - `Err(error::RecordErrorKind::InvalidValue(..).into())`
+    ///     if the header contains an invalid value.
+    /// - `Err(error::RecordErrorKind::InvalidAction(..).into())`
+    ///     if some action can not be decoded or is illegal.
+    /// - `Err(error::Error::BoardError(..).into())` if the initial board is invalid.
+    /// - `Err(error::GameRuleCreateErrorKind::InitialBoardError.into())`
+    ///     if the initial board is that of finished game.
+    /// - `Err(error::GameRuleCreateErrorKind::RepetitionLimitError(..).into())`
+    ///     or `Err(error::GameRuleCreateErrorKind::MaxPliesError(..).into())`
+    ///     if a limit is rejected by [`GameRule`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tokyodoves::Color;
+    /// use tokyodoves::game::Game;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let record = "
+    ///     remove: false
+    ///     first: Green
+    ///     board: b;B
+    ///     moves:
+    ///     +aN1E1 +AS1
+    /// ";
+    /// let game = Game::from_record(record)?;
+    /// assert!(!game.rule().is_remove_accepted());
+    /// assert_eq!(game.history().len(), 2);
+    /// assert_eq!(*game.next_player(), Color::Green);
+    /// assert_eq!(Game::from_record(&game.to_record())?.board(), game.board());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_record(record: &str) -> Result<Self, error::Error> {
+        use error::RecordErrorKind::*;
+
+        let mut rule = GameRule::default();
+        let mut lines = record
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'));
+
+        for line in lines.by_ref() {
+            let Some((key, value)) = line.split_once(':') else {
+                return Err(InvalidValue(line.to_string()).into());
+            };
+            let value = value.trim();
+            let invalid_value = || InvalidValue(line.to_string());
+            rule = match key.trim() {
+                KEY_REMOVE => {
+                    let is_remove_accepted = bool::from_str(value).map_err(|_| invalid_value())?;
+                    rule.with_is_remove_accepted(is_remove_accepted)
+                }
+                KEY_FIRST => {
+                    let first_player = parse_color(value).ok_or_else(invalid_value)?;
+                    rule.with_first_player(first_player)
+                }
+                KEY_JUDGE => {
+                    let judge = parse_judge(value).ok_or_else(invalid_value)?;
+                    rule.with_suicide_atk_judge(judge)
+                }
+                KEY_BOARD => {
+                    let initial_board = BoardBuilder::from_str(value)?.build()?;
+                    rule.with_initial_board(initial_board)?
+                }
+                KEY_REPETITION_LIMIT => {
+                    let limit = parse_limit(value).ok_or_else(invalid_value)?;
+                    rule.with_repetition_limit(limit)?
+                }
+                KEY_REPETITION_JUDGE => {
+                    let judge = parse_judge(value).ok_or_else(invalid_value)?;
+                    rule.with_repetition_judge(judge)
+                }
+                KEY_MAX_PLIES => {
+                    let max_plies = parse_limit(value).ok_or_else(invalid_value)?;
+                    rule.with_max_plies(max_plies)?
+                }
+                KEY_MAX_PLIES_JUDGE => {
+                    let judge = parse_judge(value).ok_or_else(invalid_value)?;
+                    rule.with_max_plies_judge(judge)
+                }
+                KEY_MOVES if value.is_empty() => break,
+                _ => return Err(UnknownKey(key.trim().to_string()).into()),
+            };
+        }
+
+        let mut game = Game::new_with_rule(rule);
+        for ssn in lines.flat_map(str::split_whitespace) {
+            let ply = game.ply();
+            let invalid_action = || InvalidAction(ply, ssn.to_string());
+            let action = Action::try_from_ssn(ssn, game.board()).map_err(|_| invalid_action())?;
+            game.perform(action).map_err(|_| invalid_action())?;
+        }
+        Ok(game)
+    }
+}
+
+fn format_limit(limit: &Option<usize>) -> String {
+    match limit {
+        Some(n) => n.to_string(),
+        None => String::from("none"),
+    }
+}
+
+/// Returns `Some(None)` for "none" and `None` for invalid values.
+///
+/// Any number written by [`format_limit`] is parsed back;
+/// whether it is allowed as a limit is checked by [`GameRule`].
+fn parse_limit(s: &str) -> Option<Option<usize>> {
+    match s {
+        "none" => Some(None),
+        _ => s.parse().ok().map(Some),
+    }
+}
+
+fn parse_color(s: &str) -> Option<Color> {
+    match s {
+        "Red" => Some(Color::Red),
+        "Green" => Some(Color::Green),
+        _ => None,
+    }
+}
+
+fn parse_judge(s: &str) -> Option<Judge> {
+    match s {
+        "LastWins" => Some(Judge::LastWins),
+        "NextWins" => Some(Judge::NextWins),
+        "Draw" => Some(Judge::Draw),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::GameStatus;
+    use crate::prelude::ActionContainer;
+
+    #[test]
+    fn test_record_round_trip() {
+        let rule = GameRule::new(true)
+            .with_first_player(Color::Green)
+            .with_suicide_atk_judge(Judge::LastWins)
+            .with_repetition_limit(Some(3))
+            .unwrap()
+            .with_max_plies(Some(100))
+            .unwrap()
+            .with_max_plies_judge(Judge::NextWins);
+        let mut n = 0;
+        for _ in 0..100 {
+            let mut game = Game::new_with_rule(rule);
+            while game.is_ongoing() {
+                n = (33 * n + 31) % 65536;
+                let actions = game.legal_actions();
+                game.perform(actions[n % actions.len()]).unwrap();
+            }
+            let loaded = Game::from_record(&game.to_record()).unwrap();
+            assert_eq!(loaded.history(), game.history());
+            assert_eq!(loaded.board(), game.board());
+            assert_eq!(loaded.status(), game.status());
+            assert_eq!(loaded.rule().first_player(), &Color::Green);
+            assert_eq!(loaded.rule().suicide_atk_judge(), &Judge::LastWins);
+            assert_eq!(loaded.rule().repetition_limit(), &Some(3));
+            assert_eq!(loaded.rule().max_plies(), &Some(100));
+            assert_eq!(loaded.rule().max_plies_judge(), &Judge::NextWins);
+            assert!(!matches!(loaded.status(), GameStatus::Ongoing));
+        }
+    }
+
+    #[test]
+    fn test_record_limits() {
+        for (repetition_limit, max_plies) in [(Some(2), Some(1)), (None, None)] {
+            let rule = GameRule::new(true)
+                .with_repetition_limit(repetition_limit)
+                .unwrap()
+                .with_max_plies(max_plies)
+                .unwrap();
+            let game = Game::new_with_rule(rule);
+            let loaded = Game::from_record(&game.to_record()).unwrap();
+            assert_eq!(loaded.rule().repetition_limit(), &repetition_limit);
+            assert_eq!(loaded.rule().max_plies(), &max_plies);
+        }
+
+        // Limits rejected by GameRule are rejected in the same way
+        for record in ["repetition_limit: 1\nmoves:\n", "max_plies: 0\nmoves:\n"] {
+            let err = Game::from_record(record).unwrap_err();
+            assert!(matches!(
+                err.as_game_error(),
+                Some(error::GameError::GameRuleCreateError { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_record_errors() {
+        use error::RecordErrorKind::*;
+
+        let cases = [
+            ("color: Red\nmoves:\n", UnknownKey(String::new())),
+            ("first: Blue\nmoves:\n", InvalidValue(String::new())),
+            ("max_plies: -1\nmoves:\n", InvalidValue(String::new())),
+            ("moves:\n+AS1 +AS2\n", InvalidAction(0, String::new())),
+            ("moves:\n+AS1 +AS1\n", InvalidAction(0, String::new())),
+        ];
+        for (record, expected) in cases {
+            let err = Game::from_record(record).unwrap_err();
+            let Some(error::GameError::RecordError { kind }) = err.as_game_error() else {
+                panic!("unexpected error: {err:?}");
+            };
+            assert_eq!(
+                std::mem::discriminant(kind),
+                std::mem::discriminant(&expected)
+            );
+        }
+
+        // The second action is illegal
+        let err = Game::from_record("moves:\n+AS1 +AS2\n").unwrap_err();
+        assert!(matches!(
+            err.as_game_error(),
+            Some(error::GameError::RecordError {
+                kind: InvalidAction(1, _)
+            })
+        ));
+    }
+}
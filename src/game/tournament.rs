@@ -0,0 +1,593 @@
+use std::path::Path;
+
+use crate::{
+    error,
+    game::{Agent, Game, GameRule, GameStatus},
+    prelude::{Board, Color},
+};
+
+/// A struct to let two or more [`Agent`]s play many games against each other.
+///
+/// Every pair of agents plays [`num_games`](`Tournament::with_num_games`) games
+/// under the rule given on construction.
+/// The colors of the agents are alternated game by game,
+/// so that each agent moves first in half of the games.
+/// If [`initial_boards`](`Tournament::with_initial_boards`) are specified,
+/// each board is used for a pair of games, one for each assignment of colors.
+///
+/// Unlike [`Arena`](`super::Arena`), a tournament prints nothing
+/// and returns the results as a [`TournamentResult`],
+/// which includes the record of every game.
+///
+/// Note that a game may never finish
+/// if the rule limits neither repetitions nor the number of actions.
+/// Consider [`GameRule::with_repetition_limit`] and [`GameRule::with_max_plies`]
+/// for agents that may play the same actions forever.
+///
+/// # Examples
+/// ```rust
+/// use tokyodoves::Color;
+/// use tokyodoves::game::{GameRule, MctsAgent, RandomAgent, Tournament};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let rule = GameRule::new(true).with_max_plies(Some(200))?;
+/// let mut random = RandomAgent::new().with_seed(0);
+/// let mut mcts = MctsAgent::new().with_iterations(50).with_seed(0);
+///
+/// let result = Tournament::new(rule)
+///     .with_num_games(4)
+///     .run(&mut [&mut random, &mut mcts])?;
+/// assert_eq!(result.games().len(), 4);
+///
+/// let score = result.score(1, 0); // the score of `mcts` against `random`
+/// assert_eq!(score.total().num_games(), 4);
+/// println!("red: {}, green: {}", score.as_red(), score.as_green());
+/// if let Some(elo) = score.elo_difference() {
+///     println!("{elo}");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Tournament {
+    rule: GameRule,
+    num_games: usize,
+    initial_boards: Vec<Board>,
+}
+
+impl Tournament {
+    /// Creates a tournament whose games are played under `rule`.
+    ///
+    /// Each pair of agents plays 2 games by default.
+    pub fn new(rule: GameRule) -> Self {
+        Self {
+            rule,
+            num_games: 2,
+            initial_boards: Vec::new(),
+        }
+    }
+
+    /// Returns a reference to the rule of games.
+    pub fn rule(&self) -> &GameRule {
+        &self.rule
+    }
+
+    /// Returns a reference to the number of games played by each pair of agents.
+    pub fn num_games(&self) -> &usize {
+        &self.num_games
+    }
+
+    /// Returns a reference to the initial boards of games.
+    ///
+    /// It is empty if the initial board of the rule is used for all games.
+    pub fn initial_boards(&self) -> &[Board] {
+        &self.initial_boards
+    }
+
+    /// Returns a new tournament with the specified number of games played by each pair of agents.
+    pub fn with_num_games(self, num_games: usize) -> Self {
+        Self { num_games, ..self }
+    }
+
+    /// Returns a new tournament with the specified initial boards.
+    ///
+    /// The `k`-th pair of games of each pair of agents starts from
+    /// the `k % initial_boards.len()`-th board.
+    /// If `initial_boards` is empty, the initial board of the rule is used for all games.
+    ///
+    /// # Errors
+    /// Returns `Err` if some board is already finished (at least one boss is surrounded)
+    /// in the same way as [`GameRule::with_initial_board`].
+    pub fn with_initial_boards(self, initial_boards: Vec<Board>) -> Result<Self, error::Error> {
+        for board in initial_boards.iter() {
+            self.rule.with_initial_board(*board)?;
+        }
+        Ok(Self {
+            initial_boards,
+            ..self
+        })
+    }
+
+    /// Let every pair of `agents` play games and returns the results.
+    ///
+    /// Agents are identified by their indices in `agents`.
+    /// The pairs are played in the order of `(0, 1), (0, 2), ..., (1, 2), ...`
+    /// and the agent with the smaller index plays red in the first game of each pair.
+    ///
+    /// # Errors
+    /// Returns `Err` if `agents` has less than two agents.
+    ///
+    /// # Panics
+    /// Panics if some agent performs an illegal action.
+    pub fn run(&self, agents: &mut [&mut dyn Agent]) -> Result<TournamentResult, error::Error> {
+        let num_agents = agents.len();
+        if num_agents < 2 {
+            return Err(error::TournamentErrorKind::TooFewAgents(num_agents).into());
+        }
+
+        let mut games = Vec::new();
+        for first in 0..num_agents {
+            for second in (first + 1)..num_agents {
+                for k in 0..self.num_games {
+                    let (red, green) = if k % 2 == 0 {
+                        (first, second)
+                    } else {
+                        (second, first)
+                    };
+                    let game = self.play_game(agents, red, green, k / 2)?;
+                    games.push(GameResult::new(red, green, &game));
+                }
+            }
+        }
+        Ok(TournamentResult { num_agents, games })
+    }
+
+    fn play_game(
+        &self,
+        agents: &mut [&mut dyn Agent],
+        red: usize,
+        green: usize,
+        round: usize,
+    ) -> Result<Game, error::Error> {
+        let rule = if self.initial_boards.is_empty() {
+            self.rule
+        } else {
+            let board = self.initial_boards[round % self.initial_boards.len()];
+            self.rule.with_initial_board(board)?
+        };
+        let mut game = Game::new_with_rule(rule);
+        while game.is_ongoing() {
+            match game.next_player() {
+                Color::Red => agents[red].play(&mut game),
+                Color::Green => agents[green].play(&mut game),
+            }
+        }
+        Ok(game)
+    }
+}
+
+/// A result of a game played in [`Tournament`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameResult {
+    red: usize,
+    green: usize,
+    status: GameStatus,
+    winner: Option<Color>,
+    num_plies: usize,
+    record: String,
+}
+
+impl GameResult {
+    fn new(red: usize, green: usize, game: &Game) -> Self {
+        Self {
+            red,
+            green,
+            status: *game.status(),
+            winner: game.winner(),
+            num_plies: game.ply(),
+            record: game.to_record(),
+        }
+    }
+
+    /// Returns a reference to the index of the agent who played red.
+    pub fn red(&self) -> &usize {
+        &self.red
+    }
+
+    /// Returns a reference to the index of the agent who played green.
+    pub fn green(&self) -> &usize {
+        &self.green
+    }
+
+    /// Returns the index of the agent who played `color`.
+    pub fn agent(&self, color: Color) -> usize {
+        match color {
+            Color::Red => self.red,
+            Color::Green => self.green,
+        }
+    }
+
+    /// Returns a reference to the status at the end of the game.
+    pub fn status(&self) -> &GameStatus {
+        &self.status
+    }
+
+    /// Returns a reference to the color of the winner, or `None` for a draw.
+    pub fn winner(&self) -> &Option<Color> {
+        &self.winner
+    }
+
+    /// Returns a reference to the number of actions performed in the game.
+    pub fn num_plies(&self) -> &usize {
+        &self.num_plies
+    }
+
+    /// Returns a reference to the record of the game.
+    ///
+    /// The game can be restored by [`Game::from_record`].
+    pub fn record(&self) -> &str {
+        &self.record
+    }
+}
+
+/// Results of games played in [`Tournament`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TournamentResult {
+    num_agents: usize,
+    games: Vec<GameResult>,
+}
+
+impl TournamentResult {
+    /// Returns a reference to the number of agents in the tournament.
+    pub fn num_agents(&self) -> &usize {
+        &self.num_agents
+    }
+
+    /// Returns the results of all games in the order they were played.
+    pub fn games(&self) -> &[GameResult] {
+        &self.games
+    }
+
+    /// Returns the results of `agent` against `opponent`.
+    ///
+    /// If `opponent` is `None`, games against all opponents are counted.
+    pub fn score_against(&self, agent: usize, opponent: Option<usize>) -> Score {
+        let mut score = Score::default();
+        for game in self.games.iter() {
+            let color = if game.red == agent {
+                Color::Red
+            } else if game.green == agent {
+                Color::Green
+            } else {
+                continue;
+            };
+            if opponent.is_some_and(|opponent| game.agent(!color) != opponent) {
+                continue;
+            }
+            let wld = match color {
+                Color::Red => &mut score.as_red,
+                Color::Green => &mut score.as_green,
+            };
+            match game.winner {
+                Some(winner) if winner == color => wld.wins += 1,
+                Some(_) => wld.losses += 1,
+                None => wld.draws += 1,
+            }
+        }
+        score
+    }
+
+    /// Returns the results of `agent` against `opponent`.
+    ///
+    /// It is equivalent to `self.score_against(agent, Some(opponent))`.
+    pub fn score(&self, agent: usize, opponent: usize) -> Score {
+        self.score_against(agent, Some(opponent))
+    }
+
+    /// Returns the number of actions of each game in the order they were played.
+    pub fn game_lengths(&self) -> Vec<usize> {
+        self.games.iter().map(|game| game.num_plies).collect()
+    }
+
+    /// Returns the mean number of actions per game, or `None` if no games were played.
+    pub fn mean_game_length(&self) -> Option<f64> {
+        if self.games.is_empty() {
+            return None;
+        }
+        let total: usize = self.games.iter().map(|game| game.num_plies).sum();
+        Some(total as f64 / self.games.len() as f64)
+    }
+
+    /// Saves the record of each game into a file in `dir`.
+    ///
+    /// The `i`-th game is saved as `game_{i}.txt` where `i` starts from 0.
+    /// The directory is created if it does not exist.
+    ///
+    /// # Errors
+    /// Returns `Err` if creating the directory or writing some file fails.
+    pub fn save_records<P: AsRef<Path>>(&self, dir: P) -> std::io::Result<()> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        for (i, game) in self.games.iter().enumerate() {
+            std::fs::write(dir.join(format!("game_{i}.txt")), &game.record)?;
+        }
+        Ok(())
+    }
+}
+
+/// Numbers of wins, losses and draws of an agent.
+///
+/// With the [`Display`](`std::fmt::Display`) trait,
+/// it is written like `+3 -1 =2` (wins, losses and draws).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WinLossDraw {
+    wins: usize,
+    losses: usize,
+    draws: usize,
+}
+
+impl std::fmt::Display for WinLossDraw {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "+{} -{} ={}", self.wins, self.losses, self.draws)
+    }
+}
+
+impl std::ops::Add for WinLossDraw {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            wins: self.wins + rhs.wins,
+            losses: self.losses + rhs.losses,
+            draws: self.draws + rhs.draws,
+        }
+    }
+}
+
+impl WinLossDraw {
+    /// Returns a reference to the number of wins.
+    pub fn wins(&self) -> &usize {
+        &self.wins
+    }
+
+    /// Returns a reference to the number of losses.
+    pub fn losses(&self) -> &usize {
+        &self.losses
+    }
+
+    /// Returns a reference to the number of draws.
+    pub fn draws(&self) -> &usize {
+        &self.draws
+    }
+
+    /// Returns the number of games.
+    pub fn num_games(&self) -> usize {
+        self.wins + self.losses + self.draws
+    }
+
+    /// Returns the score rate, where a win counts 1 and a draw counts 1/2,
+    /// or `None` if no games were played.
+    pub fn score_rate(&self) -> Option<f64> {
+        let num_games = self.num_games();
+        if num_games == 0 {
+            return None;
+        }
+        Some((self.wins as f64 + 0.5 * self.draws as f64) / num_games as f64)
+    }
+}
+
+/// Results of an agent separated by its color.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Score {
+    as_red: WinLossDraw,
+    as_green: WinLossDraw,
+}
+
+impl Score {
+    /// Returns a reference to the results of games where the agent played red.
+    pub fn as_red(&self) -> &WinLossDraw {
+        &self.as_red
+    }
+
+    /// Returns a reference to the results of games where the agent played green.
+    pub fn as_green(&self) -> &WinLossDraw {
+        &self.as_green
+    }
+
+    /// Returns the results of games where the agent played `color`.
+    pub fn as_color(&self, color: Color) -> &WinLossDraw {
+        match color {
+            Color::Red => &self.as_red,
+            Color::Green => &self.as_green,
+        }
+    }
+
+    /// Returns the results of all games.
+    pub fn total(&self) -> WinLossDraw {
+        self.as_red + self.as_green
+    }
+
+    /// Estimates the Elo rating difference from the results of all games.
+    ///
+    /// Returns `None` if no games were played.
+    /// See [`EloEstimate`] for details.
+    pub fn elo_difference(&self) -> Option<EloEstimate> {
+        EloEstimate::from_results(&self.total())
+    }
+}
+
+/// An estimate of the Elo rating difference between two agents
+/// with its 95% confidence interval.
+///
+/// The difference is calculated from the score rate `p` as `-400 * log10(1 / p - 1)`.
+/// The confidence interval is obtained by converting `p ± 1.96 * σ`,
+/// where `σ` is the standard error of the score rate over the games.
+/// Values are infinite if the score rate (or its bound) is 0 or 1.
+///
+/// With the [`Display`](`std::fmt::Display`) trait,
+/// it is written like `+52.3 [-10.5, +115.1]`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EloEstimate {
+    difference: f64,
+    lower: f64,
+    upper: f64,
+}
+
+impl std::fmt::Display for EloEstimate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:+.1} [{:+.1}, {:+.1}]",
+            self.difference, self.lower, self.upper
+        )
+    }
+}
+
+impl EloEstimate {
+    const Z_95: f64 = 1.96;
+
+    /// Estimates the Elo rating difference from the results,
+    /// or returns `None` if no games were played.
+    pub fn from_results(results: &WinLossDraw) -> Option<Self> {
+        let p = results.score_rate()?;
+        let n = results.num_games() as f64;
+        let variance = (results.wins as f64 * (1.0 - p).powi(2)
+            + results.draws as f64 * (0.5 - p).powi(2)
+            + results.losses as f64 * p.powi(2))
+            / n;
+        let margin = Self::Z_95 * (variance / n).sqrt();
+        Some(Self {
+            difference: elo_from_score_rate(p),
+            lower: elo_from_score_rate((p - margin).max(0.0)),
+            upper: elo_from_score_rate((p + margin).min(1.0)),
+        })
+    }
+
+    /// Returns a reference to the estimated difference.
+    pub fn difference(&self) -> &f64 {
+        &self.difference
+    }
+
+    /// Returns a reference to the lower bound of the 95% confidence interval.
+    pub fn lower(&self) -> &f64 {
+        &self.lower
+    }
+
+    /// Returns a reference to the upper bound of the 95% confidence interval.
+    pub fn upper(&self) -> &f64 {
+        &self.upper
+    }
+}
+
+fn elo_from_score_rate(p: f64) -> f64 {
+    -400.0 * (1.0 / p - 1.0).log10()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{MctsAgent, RandomAgent};
+
+    #[test]
+    fn test_tournament() {
+        let rule = GameRule::new(true).with_max_plies(Some(100)).unwrap();
+        let mut agent0 = RandomAgent::new().with_seed(0);
+        let mut agent1 = RandomAgent::new().with_seed(1);
+        let mut agent2 = MctsAgent::new().with_iterations(20).with_seed(2);
+        let result = Tournament::new(rule)
+            .with_num_games(4)
+            .run(&mut [&mut agent0, &mut agent1, &mut agent2])
+            .unwrap();
+        assert_eq!(result.games().len(), 12);
+
+        for (i, game) in result.games().iter().enumerate() {
+            // Colors are alternated
+            let pair = [(0, 1), (0, 2), (1, 2)][i / 4];
+            let expected = if i % 2 == 0 { pair } else { (pair.1, pair.0) };
+            assert_eq!((*game.red(), *game.green()), expected);
+
+            let restored = Game::from_record(game.record()).unwrap();
+            assert_eq!(restored.status(), game.status());
+            assert_eq!(restored.ply(), *game.num_plies());
+        }
+        assert_eq!(result.game_lengths().len(), 12);
+        assert!(result.mean_game_length().unwrap() > 0.0);
+
+        for agent in 0..3 {
+            let total = result.score_against(agent, None).total();
+            assert_eq!(total.num_games(), 8);
+            for opponent in (0..3).filter(|&opponent| opponent != agent) {
+                let score = result.score(agent, opponent);
+                let reversed = result.score(opponent, agent);
+                assert_eq!(score.as_red().num_games(), 2);
+                assert_eq!(score.as_green().num_games(), 2);
+                assert_eq!(score.as_red().wins(), reversed.as_green().losses());
+                assert_eq!(score.as_red().draws(), reversed.as_green().draws());
+            }
+        }
+    }
+
+    #[test]
+    fn test_tournament_with_initial_boards() {
+        use crate::BoardBuilder;
+        use std::str::FromStr;
+
+        let boards = ["bB", "b;B", "bB;T YA"]
+            .map(|s| BoardBuilder::from_str(s).unwrap().build().unwrap())
+            .to_vec();
+        let mut agent0 = RandomAgent::new().with_seed(0);
+        let mut agent1 = RandomAgent::new().with_seed(1);
+        let result = Tournament::new(GameRule::new(true))
+            .with_num_games(6)
+            .with_initial_boards(boards.clone())
+            .unwrap()
+            .run(&mut [&mut agent0, &mut agent1])
+            .unwrap();
+        for (i, game) in result.games().iter().enumerate() {
+            let restored = Game::from_record(game.record()).unwrap();
+            assert_eq!(*restored.rule().initial_board(), boards[i / 2]);
+        }
+
+        let finished = BoardBuilder::from_str("bBh;Aa m;  M; H Y")
+            .unwrap()
+            .build()
+            .unwrap();
+        assert!(Tournament::new(GameRule::new(true))
+            .with_initial_boards(vec![finished])
+            .is_err());
+        assert!(Tournament::new(GameRule::new(true))
+            .run(&mut [&mut agent0])
+            .is_err());
+    }
+
+    #[test]
+    fn test_elo_estimate() {
+        let even = WinLossDraw {
+            wins: 10,
+            losses: 10,
+            draws: 5,
+        };
+        let elo = EloEstimate::from_results(&even).unwrap();
+        assert!(elo.difference().abs() < 1e-9);
+        assert!(*elo.lower() < 0.0 && *elo.upper() > 0.0);
+        assert!((elo.lower() + elo.upper()).abs() < 1e-9);
+
+        let strong = WinLossDraw {
+            wins: 30,
+            losses: 10,
+            draws: 0,
+        };
+        let elo = EloEstimate::from_results(&strong).unwrap();
+        // 75% corresponds to about 191 points
+        assert!((elo.difference() - 190.85).abs() < 0.1);
+        assert!(elo.lower() < elo.difference() && elo.difference() < elo.upper());
+
+        let perfect = WinLossDraw {
+            wins: 3,
+            losses: 0,
+            draws: 0,
+        };
+        let elo = EloEstimate::from_results(&perfect).unwrap();
+        assert_eq!(*elo.difference(), f64::INFINITY);
+        assert!(EloEstimate::from_results(&WinLossDraw::default()).is_none());
+    }
+}
@@ -191,11 +191,16 @@
 //! These modules are available if you indicate the feature "analysis".
 //!
 //! # About feature flags
-//! The following three features are available:
+//! The following four features are available:
 //! - no flag: Only basic board and related entities are included
 //! - "game": Entities related to game playing are included (those which are described in "Play more" section).
 //! - "analysis": Tools for analysis and collections are included (those which are described in "Analyze the game" section).
 //! It also includes those for "game" feature, and [`AnalystAgent`](`game::AnalystAgent`) additionally.
+//! - "serde": Implementations of `Serialize` and `Deserialize` of [serde](https://serde.rs/)
+//!   are included for data types such as [`Board`], [`Action`] and [`Color`]
+//!   (and those in other modules if the corresponding features are indicated).
+//!   [`Board`] is serialized as a simple string like `"b   ;B   ;    ;    "` in human-readable formats (e.g. JSON)
+//!   and as the `u64` returned by [`Board::to_u64`] in compact formats (e.g. bincode).
 //!
 //! See "features" page of "The Cargo Book" below for information about how to indicate features:<br>
 //! <https://doc.rust-lang.org/cargo/reference/features.html>
@@ -4,9 +4,11 @@ pub(crate) mod builder;
 pub(crate) mod macros;
 pub(crate) mod pieces;
 pub(crate) mod shift;
+pub(crate) mod symmetry;
 
 pub use actions::Action;
 pub use board::main::*;
 pub use builder::*;
 pub use pieces::*;
 pub use shift::*;
+pub use symmetry::Symmetry;
@@ -2,7 +2,7 @@ use crate::error;
 use crate::prelude::{
     board::main::Board,
     pieces::{color_dove_to_char, try_char_to_color_dove, Color, Dove},
-    Shift,
+    Shift, Symmetry,
 };
 
 /// Actions players can perform in their turn.
@@ -47,6 +47,7 @@ use crate::prelude::{
 ///
 /// Now the notation above is named SSN.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Action {
     /// Put [`Dove`] from [`Color`]'s hand on the board
     /// at the position shifted from [`Color`]'s boss-hato
@@ -117,6 +118,59 @@ impl Action {
         }
     }
 
+    /// Returns the action transformed by `sym`.
+    ///
+    /// The shift of the action is transformed by [`Shift::transform`].
+    /// If `action` is legal on `board`,
+    /// `action.transform(sym)` is legal on `board.transform(sym)`.
+    /// See [`Symmetry`] for details.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tokyodoves::{Action, Board, Color, Dove, Shift, Symmetry};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let action = Action::Put(Color::Red, Dove::A, Shift::new(0, 1));
+    /// let transformed = action.transform(Symmetry::Rotate90);
+    /// assert_eq!(transformed, Action::Put(Color::Red, Dove::A, Shift::new(1, 0)));
+    ///
+    /// let mut board = Board::new();
+    /// board.perform(action)?;
+    /// let mut rotated = Board::new().transform(Symmetry::Rotate90);
+    /// rotated.perform(transformed)?;
+    /// assert_eq!(rotated, board.transform(Symmetry::Rotate90));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn transform(&self, sym: Symmetry) -> Action {
+        use Action::*;
+        match *self {
+            Put(c, d, s) => Put(c, d, s.transform(sym)),
+            Move(c, d, s) => Move(c, d, s.transform(sym)),
+            Remove(c, d) => Remove(c, d),
+        }
+    }
+
+    /// Swaps the color of the player of the action.
+    ///
+    /// If `action` is legal on `board`,
+    /// the swapped action is legal on the board swapped by [`Board::swap_color`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tokyodoves::{Action, Color, Dove};
+    ///
+    /// let mut action = Action::Remove(Color::Red, Dove::H);
+    /// action.swap_color();
+    /// assert_eq!(action, Action::Remove(Color::Green, Dove::H));
+    /// ```
+    pub fn swap_color(&mut self) {
+        use Action::*;
+        match self {
+            Put(c, _, _) | Move(c, _, _) | Remove(c, _) => *c = !*c,
+        }
+    }
+
     /// Converts `self` into `String` in SSN.
     ///
     /// See the documentation of [`Action`] for the definition of SSN.
@@ -6,3 +6,4 @@ pub(crate) mod main;
 pub(crate) mod mask;
 pub(crate) mod position;
 pub(crate) mod route;
+pub(crate) mod zobrist;
@@ -4,12 +4,14 @@ use crate::error;
 
 use crate::prelude::{
     actions::Action,
+    builder::BoardBuilder,
     pieces::{color_to_index, dove_to_index, Color, Dove},
     shift::Shift,
+    symmetry::Symmetry,
 };
 
 use crate::prelude::board::{
-    bitutil::*, canonicalizer::*, container::*, mask::*, position::*, route::*,
+    bitutil::*, canonicalizer::*, container::*, mask::*, position::*, route::*, zobrist,
 };
 
 pub use crate::prelude::board::{
@@ -148,7 +150,7 @@ pub struct ActionsFwdIntoIter(FiniteActionContainerIntoIter<64>);
 ///
 /// Its contents are in the stack memory.
 #[derive(Clone)]
-pub struct ActionsBwd(FiniteActionContainer<100>);
+pub struct ActionsBwd(FiniteActionContainer<256>);
 
 /// An [`Iterator`] returned by
 /// [`iter`](`ActionsBwd::iter`) method
@@ -160,7 +162,7 @@ pub struct ActionsBwdIter<'a>(FiniteActionContainerIter<'a>);
 /// the [`into_iter`](`ActionsBwd::into_iter`) method
 /// on [`ActionsBwd`].
 #[derive(Clone)]
-pub struct ActionsBwdIntoIter(FiniteActionContainerIntoIter<100>);
+pub struct ActionsBwdIntoIter(FiniteActionContainerIntoIter<256>);
 
 impl_mutable_action_container! {
     ActionsFwd {
@@ -169,7 +171,7 @@ impl_mutable_action_container! {
         ActionsFwdIntoIter, "ActionsFwdIntoIter"
     }
     ActionsBwd {
-        FiniteActionContainer<100>,
+        FiniteActionContainer<256>,
         ActionsBwdIter, "ActionsBwdIter",
         ActionsBwdIntoIter, "ActionsBwdIntoIter"
     }
@@ -239,6 +241,22 @@ pub enum SurroundedStatus {
     None,
 }
 
+/// A token to revert an action performed by [`Board::make`].
+///
+/// It holds the state of [`Board`] changed by the action,
+/// and is consumed by [`Board::unmake`].
+/// A token must be passed to the board which created it
+/// and tokens must be consumed in the reverse order of creation.
+#[derive(Debug, Clone, Copy)]
+pub struct UndoToken {
+    viewer: MaskViewer,
+    color: Color,
+    dove: Dove,
+    position: u64,
+    hash: u64,
+    symmetric_hashes: zobrist::SymmetricHashes,
+}
+
 // *******************************************************************
 //  Implement Traits
 // *******************************************************************
@@ -250,6 +268,10 @@ pub enum SurroundedStatus {
 pub struct Board {
     pub(crate) viewer: MaskViewer,
     pub(crate) positions: ColorDovePositions,
+    /// Zobrist hash updated incrementally
+    hash: u64,
+    /// Zobrist hashes of the board under 8 symmetries updated incrementally
+    symmetric_hashes: zobrist::SymmetricHashes,
 }
 
 impl std::fmt::Debug for Board {
@@ -260,7 +282,14 @@ impl std::fmt::Debug for Board {
 
 impl Board {
     pub(crate) fn from_components(viewer: MaskViewer, positions: ColorDovePositions) -> Self {
-        Self { viewer, positions }
+        let hash = zobrist::hash(&viewer, &positions);
+        let symmetric_hashes = zobrist::symmetric_hashes(&viewer, &positions);
+        Self {
+            viewer,
+            positions,
+            hash,
+            symmetric_hashes,
+        }
     }
 }
 
@@ -272,7 +301,8 @@ impl std::fmt::Display for Board {
 
 impl PartialEq for Board {
     fn eq(&self, other: &Self) -> bool {
-        self.to_u64() == other.to_u64()
+        // Compare hashes first because they are much cheaper than u64 expressions
+        self.zobrist_hash() == other.zobrist_hash() && self.to_u64() == other.to_u64()
     }
 }
 
@@ -286,7 +316,38 @@ impl Default for Board {
 
 impl std::hash::Hash for Board {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        state.write_u64(self.to_u64());
+        state.write_u64(self.zobrist_hash());
+    }
+}
+
+/// Serializes the board as [`to_simple_string`](`Board::to_simple_string`)
+/// with `' '` and `";"` in human-readable formats (e.g. JSON),
+/// and as [`to_u64`](`Board::to_u64`) in compact formats (e.g. bincode).
+#[cfg(feature = "serde")]
+impl serde::Serialize for Board {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&self.to_simple_string(' ', ";"))
+        } else {
+            serializer.serialize_u64(self.to_u64())
+        }
+    }
+}
+
+/// Deserializes the board in the same encoding as [`Serialize`](`serde::Serialize`),
+/// checking that the board is valid as [`BoardBuilder::build`](`crate::BoardBuilder::build`) does.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Board {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+        use std::str::FromStr;
+        let builder = if deserializer.is_human_readable() {
+            let s = String::deserialize(deserializer)?;
+            crate::BoardBuilder::from_str(&s).map_err(D::Error::custom)?
+        } else {
+            crate::BoardBuilder::from_u64(u64::deserialize(deserializer)?)
+        };
+        builder.build().map_err(D::Error::custom)
     }
 }
 
@@ -476,25 +537,31 @@ impl Board {
 
     fn perform_unchecked_raw(&mut self, action: Action) -> Result<(), error::Error> {
         use Action::*;
-        match action {
+        let (c, d, pos_now, pos_next) = match action {
             Put(c, d, s) => {
                 let boss_pos = self.positions.position_of(c, Dove::B);
-                let pos = apply_shift(*boss_pos, s);
-                self.viewer
-                    .shift_toward(pos)
-                    .map_err(|_| error::BoardError::InternalError)?;
-                self.positions.set_position(c, d, pos);
+                (c, d, 0, apply_shift(*boss_pos, s))
             }
             Move(c, d, s) => {
-                let pos_now = self.positions.position_of(c, d);
-                let pos_next = apply_shift(*pos_now, s);
-                self.viewer
-                    .shift_toward(pos_next)
-                    .map_err(|_| error::BoardError::InternalError)?;
-                self.positions.set_position(c, d, pos_next);
+                let pos_now = *self.positions.position_of(c, d);
+                (c, d, pos_now, apply_shift(pos_now, s))
             }
-            Remove(c, d) => self.positions.set_position(c, d, 0),
+            Remove(c, d) => (c, d, *self.positions.position_of(c, d), 0),
+        };
+        if pos_now != 0 {
+            zobrist::toggle_symmetric(&mut self.symmetric_hashes, c, d, pos_now, &self.viewer);
+        }
+        if pos_next != 0 {
+            let status = self.viewer.status();
+            self.viewer
+                .shift_toward(pos_next)
+                .map_err(|_| error::BoardError::InternalError)?;
+            self.hash = zobrist::shift_view(self.hash, status, &self.viewer);
+            zobrist::shift_view_symmetric(&mut self.symmetric_hashes, status, &self.viewer);
+            zobrist::toggle_symmetric(&mut self.symmetric_hashes, c, d, pos_next, &self.viewer);
         }
+        self.positions.set_position(c, d, pos_next);
+        self.hash = zobrist::move_dove(self.hash, c, d, pos_now, pos_next, &self.viewer);
         Ok(())
     }
 
@@ -536,6 +603,78 @@ impl Board {
         board
     }
 
+    /// Performs `action` to `self` without legality check
+    /// and returns a token to revert it by [`unmake`](`Self::unmake`).
+    ///
+    /// This method behaves the same as [`perform_unchecked`](`Self::perform_unchecked`).
+    /// The pair of `make` and `unmake` is useful to search positions
+    /// without copying the board at every step.
+    ///
+    /// # Panics
+    /// Panics in the same cases as [`perform_unchecked`](`Self::perform_unchecked`).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tokyodoves::{Board, Action, Color, Dove, Shift};
+    ///
+    /// let mut board = Board::new();
+    /// let original = board;
+    /// let action = Action::Put(Color::Red, Dove::A, Shift::new(0, 1));
+    /// let token = board.make(action);
+    /// assert_eq!(board, original.perform_unchecked_copied(action));
+    /// board.unmake(token);
+    /// assert_eq!(board, original);
+    /// ```
+    pub fn make(&mut self, action: Action) -> UndoToken {
+        let (color, dove) = (*action.player(), *action.dove());
+        let token = UndoToken {
+            viewer: self.viewer,
+            color,
+            dove,
+            position: *self.positions.position_of(color, dove),
+            hash: self.hash,
+            symmetric_hashes: self.symmetric_hashes,
+        };
+        self.perform_unchecked(action);
+        token
+    }
+
+    /// Reverts the action performed by [`make`](`Self::make`) which returned `token`.
+    ///
+    /// Both positions of doves and the 4x4 field viewed on the board are restored exactly,
+    /// differently from performing an inverse action by [`perform_bwd`](`Self::perform_bwd`),
+    /// which may leave the field translated.
+    /// The result is unspecified if `token` was not created by the last call of `make` on `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tokyodoves::{Board, Action, Color, Dove, Shift};
+    ///
+    /// let mut board = Board::new();
+    /// let original = board;
+    /// let token1 = board.make(Action::Put(Color::Red, Dove::A, Shift::new(0, 1)));
+    /// let token2 = board.make(Action::Put(Color::Green, Dove::A, Shift::new(0, -1)));
+    /// board.unmake(token2);
+    /// board.unmake(token1);
+    /// assert_eq!(board, original);
+    /// ```
+    pub fn unmake(&mut self, token: UndoToken) {
+        let UndoToken {
+            viewer,
+            color,
+            dove,
+            position,
+            hash,
+            symmetric_hashes,
+        } = token;
+        self.viewer = viewer;
+        self.positions.set_position(color, dove, position);
+        self.hash = hash;
+        self.symmetric_hashes = symmetric_hashes;
+    }
+
     // *******************************************************************
     //  Methods For Actions Check
     // *******************************************************************
@@ -759,6 +898,214 @@ impl Board {
         )
     }
 
+    /// Collects and returns legal [`Action`]s performed by the specified player,
+    /// one for each class of equivalent resulting boards.
+    ///
+    /// Two actions are equivalent if the resulting boards have the same
+    /// [`to_invariant_u64`](`Self::to_invariant_u64`),
+    /// i.e., they coincide under rotations, reflections and translations.
+    /// Such actions have the same value in the game,
+    /// so searches need to examine only one of them.
+    /// The first action in the order of [`legal_actions`](`Self::legal_actions`)
+    /// represents each class.
+    ///
+    /// See [`legal_actions_unique_with_map`](`Self::legal_actions_unique_with_map`)
+    /// to know which representative each action corresponds to.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tokyodoves::{ActionContainer, Board, Color};
+    ///
+    /// let board = Board::new();
+    /// let actions = board.legal_actions(Color::Red, true, true, true);
+    /// let unique_actions = board.legal_actions_unique(Color::Red, true, true, true);
+    /// // The initial board is symmetric about the vertical line
+    /// assert!(unique_actions.len() < actions.len());
+    /// ```
+    pub fn legal_actions_unique(
+        &self,
+        player: Color,
+        contains_put: bool,
+        contains_move: bool,
+        contains_remove: bool,
+    ) -> ActionsFwd {
+        let actions = self.legal_actions(player, contains_put, contains_move, contains_remove);
+        let mut keys = std::collections::HashSet::new();
+        let mut unique_actions = ActionsFwd::new();
+        for action in actions {
+            let next_board = self.perform_unchecked_copied(action);
+            if keys.insert(next_board.to_invariant_u64(!player)) {
+                unique_actions.push(action);
+            }
+        }
+        unique_actions
+    }
+
+    /// Collects legal [`Action`]s in the same way as
+    /// [`legal_actions_unique`](`Self::legal_actions_unique`)
+    /// and returns them together with a map from each legal action to its representative.
+    ///
+    /// The map contains all actions returned by [`legal_actions`](`Self::legal_actions`)
+    /// as its keys, and each representative is mapped to itself.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tokyodoves::{Action, Board, Color, Dove, Shift};
+    ///
+    /// let board = Board::new();
+    /// let (actions, map) = board.legal_actions_unique_with_map(Color::Red, true, true, true);
+    /// // Putting on the left and the right of the boss are equivalent
+    /// let left = Action::Put(Color::Red, Dove::A, Shift::new(0, -1));
+    /// let right = Action::Put(Color::Red, Dove::A, Shift::new(0, 1));
+    /// assert_eq!(map[&left], map[&right]);
+    /// for action in actions {
+    ///     assert_eq!(map[&action], action);
+    /// }
+    /// ```
+    pub fn legal_actions_unique_with_map(
+        &self,
+        player: Color,
+        contains_put: bool,
+        contains_move: bool,
+        contains_remove: bool,
+    ) -> (ActionsFwd, std::collections::HashMap<Action, Action>) {
+        let actions = self.legal_actions(player, contains_put, contains_move, contains_remove);
+        let mut representatives = std::collections::HashMap::new();
+        let mut unique_actions = ActionsFwd::new();
+        let mut map = std::collections::HashMap::new();
+        for action in actions {
+            let next_board = self.perform_unchecked_copied(action);
+            let representative = *representatives
+                .entry(next_board.to_invariant_u64(!player))
+                .or_insert_with(|| {
+                    unique_actions.push(action);
+                    action
+                });
+            map.insert(action, representative);
+        }
+        (unique_actions, map)
+    }
+
+    /// Collects and returns [`Action`]s of `player`
+    /// that surround the opponent's boss immediately.
+    ///
+    /// Actions that surround both bosses are not included
+    /// because their results depend on the rule
+    /// (see [`Judge`](`crate::game::Judge`) in the [`game`](`crate::game`) module).
+    ///
+    /// Instead of performing all legal actions,
+    /// only puts and moves to candidate destinations are generated
+    /// and their results are judged by bit operations:
+    /// an action can decrease the liberty of the opponent's boss (see [`liberty_of_boss`](`Self::liberty_of_boss`))
+    /// only if its destination is next to the boss or outside the minimum rectangle
+    /// spanning three or four squares (the latter may create walls).
+    /// Removing a dove never decreases the liberty.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::str::FromStr;
+    /// use tokyodoves::{Action, BoardBuilder, Color, Dove, Shift};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let board = BoardBuilder::from_str(" Byt;H Ma;A m ;  Yb")?.build()?;
+    /// let actions = board.winning_actions(Color::Red);
+    /// for action in actions {
+    ///     let next_board = board.perform_copied(action)?;
+    ///     assert_eq!(next_board.liberty_of_boss(Color::Green), 0);
+    ///     assert!(next_board.liberty_of_boss(Color::Red) > 0);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn winning_actions(&self, player: Color) -> ActionsFwd {
+        self.surrounding_actions(player, false)
+    }
+
+    /// Collects and returns [`Action`]s of the opponent of `player`
+    /// that surround `player`'s boss immediately.
+    ///
+    /// It is equivalent to `self.winning_actions(!player)`,
+    /// which is useful to warn `player` of the threats like "check" in chess.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::str::FromStr;
+    /// use tokyodoves::{ActionContainer, Board, BoardBuilder, Color};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// assert!(Board::new().threats(Color::Red).is_empty());
+    /// let board = BoardBuilder::from_str(" Byt;H Ma;A m ;  Yb")?.build()?;
+    /// assert!(!board.threats(Color::Green).is_empty());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn threats(&self, player: Color) -> ActionsFwd {
+        self.winning_actions(!player)
+    }
+
+    /// Collects actions of `player` that surround the opponent's boss.
+    ///
+    /// Actions that surround both bosses are included if `includes_both` is `true`.
+    pub(crate) fn surrounding_actions(&self, player: Color, includes_both: bool) -> ActionsFwd {
+        let mut actions = ActionsFwd::new();
+        let all = self.positions.union();
+        let walls = self.viewer.view_mask().calc_wall_bits(all);
+        let target = *self.positions.position_of(!player, Dove::B);
+        let target_sides = sides_of_bit(target);
+        // An action fills one square and creates at most two walls (horizontal and vertical)
+        if (target_sides & !all & !walls).count_ones() > 3 {
+            return actions;
+        }
+
+        // A wall appears only when the minimum rectangle expands or moves
+        // spanning four squares
+        let mask = self.viewer.view_mask();
+        let rectangle = mask.minimum_rectangle(all);
+        let size = rectangle.size();
+        // Bands of the columns and the rows of the rectangle extended to the whole `u64`
+        let row = ((1_u64 << size.hsize) - 1) << rectangle.hmin;
+        let columns = (row * 0x0101010101010101).rotate_left(self.viewer.status() as u32);
+        let rows = (((1_u64 << (8 * size.vsize)) - 1) << (8 * rectangle.vmin))
+            .rotate_left(self.viewer.status() as u32);
+        let mut wall_creators = 0;
+        if size.hsize >= 3 {
+            wall_creators |= !columns;
+        }
+        if size.vsize >= 3 {
+            wall_creators |= !rows;
+        }
+        let destinations = (target_sides | wall_creators) & !all;
+        if destinations == 0 {
+            return actions;
+        }
+
+        let mut candidates = ActionsFwd::new();
+        self.pack_puts(&mut candidates, player, true, destinations);
+        self.pack_moves(&mut candidates, player, destinations);
+        let boss_pos = *self.positions.position_of(player, Dove::B);
+        for action in candidates {
+            let (pos_now, dst) = match action {
+                Action::Put(_, _, shift) => (0, apply_shift(boss_pos, shift)),
+                Action::Move(_, dove, shift) => {
+                    let pos_now = *self.positions.position_of(player, dove);
+                    (pos_now, apply_shift(pos_now, shift))
+                }
+                Action::Remove(_, _) => continue,
+            };
+            let Ok(next_mask) = self.viewer.view_mask_at(dst) else {
+                continue;
+            };
+            let next_all = (all & !pos_now) | dst;
+            let next_walls = next_mask.calc_wall_bits(next_all);
+            let is_surrounded = |pos: u64| sides_of_bit(pos) & !next_all & !next_walls == 0;
+            let next_boss_pos = if pos_now == boss_pos { dst } else { boss_pos };
+            if is_surrounded(target) && (includes_both || !is_surrounded(next_boss_pos)) {
+                actions.push(action);
+            }
+        }
+        actions
+    }
+
     /// Collects and returns all [`Action`]s legal in backward direction
     /// performed by the specified player.
     ///
@@ -806,10 +1153,10 @@ impl Board {
     ) -> T {
         let mut actions = T::new();
         if contains_put {
-            self.pack_puts(&mut actions, player, fwd);
+            self.pack_puts(&mut actions, player, fwd, u64::MAX);
         }
         if contains_move {
-            self.pack_moves(&mut actions, player);
+            self.pack_moves(&mut actions, player, u64::MAX);
         }
         if contains_remove {
             self.pack_removes(&mut actions, player, fwd);
@@ -817,7 +1164,14 @@ impl Board {
         actions
     }
 
-    fn pack_puts(&self, actions: &mut impl MutableActionContainer, player: Color, fwd: bool) {
+    /// Packs puts of `player` whose destinations are in `destinations`.
+    fn pack_puts(
+        &self,
+        actions: &mut impl MutableActionContainer,
+        player: Color,
+        fwd: bool,
+        destinations: u64,
+    ) {
         let doves_in_hand = self.positions.doves_in_hand(player);
         // skip if no dove is in hand
         if !doves_in_hand.is_empty() {
@@ -841,7 +1195,7 @@ impl Board {
             };
 
             let boss_pos = self.positions.position_of(player, Dove::B);
-            for dst in HotBitIter::from(possibles) {
+            for dst in HotBitIter::from(possibles & destinations) {
                 let shift = create_shift_from_bits(*boss_pos, dst);
                 for d in doves_in_hand {
                     actions.push(Action::Put(player, d, shift));
@@ -850,7 +1204,13 @@ impl Board {
         }
     }
 
-    fn pack_moves(&self, actions: &mut impl MutableActionContainer, player: Color) {
+    /// Packs moves of `player` whose destinations are in `destinations`.
+    fn pack_moves(
+        &self,
+        actions: &mut impl MutableActionContainer,
+        player: Color,
+        destinations: u64,
+    ) {
         let doves_on_field = self.positions.doves_on_field(player);
         let outfield = self.viewer.view_mask().outfield;
         for d in doves_on_field {
@@ -858,12 +1218,16 @@ impl Board {
             let others = self.positions.union_except(player, d);
             let adj_others = calc_adjacents(others);
             for (pos_next, route, (dh, dv)) in bit_route_shift(*pos_now, d) {
+                if pos_next & destinations == 0 {
+                    continue; // not a destination of interest
+                }
+
                 if route & (others | outfield) != 0 {
                     continue; // obstacle in route
                 }
 
                 let Ok(next_mask) = self.viewer.view_mask_at(*pos_next) else {
-                    continue // out of field
+                    continue; // out of field
                 };
 
                 if others & !next_mask.core != 0 {
@@ -936,6 +1300,8 @@ impl Board {
     /// ```
     pub fn swap_color(&mut self) {
         self.positions.swap_color();
+        self.hash = zobrist::hash(&self.viewer, &self.positions);
+        self.symmetric_hashes = zobrist::symmetric_hashes(&self.viewer, &self.positions);
     }
 
     // *******************************************************************
@@ -1412,7 +1778,9 @@ impl Board {
                     continue;
                 }
                 let ishift = 11 - (2 * id + ic);
-                let Some(ipos_raw) = board.viewer.view_mask().field_idx(*pos) else { continue; };
+                let Some(ipos_raw) = board.viewer.view_mask().field_idx(*pos) else {
+                    continue;
+                };
                 let ipos = ipos_raw - idx_shift;
                 for (i, hash) in hashes.iter_mut().enumerate() {
                     let new_pos = mapper.map(i, ipos) as u64;
@@ -1424,6 +1792,226 @@ impl Board {
         hashes.into_iter().min().unwrap()
     }
 
+    /// Returns the board equivalent to `self` viewed from `next_player`,
+    /// where the next player is red.
+    ///
+    /// Equivalent boards in the sense of [`to_invariant_u64`](`Self::to_invariant_u64`)
+    /// are normalized into the same board.
+    #[cfg(feature = "analysis")]
+    pub(crate) fn normalize(&self, next_player: Color) -> Board {
+        BoardBuilder::from_u64(self.to_invariant_u64(next_player)).build_unchecked()
+    }
+
+    /// Returns whether the board is mapped onto itself (up to translation)
+    /// by some [`Symmetry`] other than the identity.
+    #[cfg(feature = "analysis")]
+    pub(crate) fn is_symmetric(&self) -> bool {
+        // Every dove is unique, so such a symmetry must fix all doves.
+        // It is possible only for a reflection whose axis passes through all doves.
+        let Rectangle {
+            hmin,
+            hmax,
+            vmin,
+            vmax,
+        } = self.minimum_rectangle();
+        if hmin == hmax || vmin == vmax {
+            return true;
+        }
+        if hmax - hmin != vmax - vmin {
+            return false;
+        }
+        let occupied: Vec<(usize, usize)> = self
+            .to_4x4_matrix()
+            .iter()
+            .enumerate()
+            .flat_map(|(v, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter(|(_, cell)| cell.is_some())
+                    .map(move |(h, _)| (v, h))
+            })
+            .collect();
+        occupied.iter().all(|&(v, h)| v - vmin == h - hmin)
+            || occupied.iter().all(|&(v, h)| v - vmin == hmax - h)
+    }
+
+    /// Returns a light expression of `u64` invariant under translation.
+    ///
+    /// The returned value is the same as the return value of the [`to_u64`](`Self::to_u64`) method
+    /// on the board translated so that all doves are as close to the top-left corner as possible.
+    /// Differently from [`to_invariant_u64`](`Self::to_invariant_u64`),
+    /// boards coinciding under reflection or rotation return different values.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::str::FromStr;
+    /// use tokyodoves::{Board, BoardBuilder};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let board = BoardBuilder::from_str(";; b; B")?.build()?;
+    /// assert_ne!(board.to_u64(), Board::new().to_u64());
+    /// assert_eq!(
+    ///     board.to_translation_invariant_u64(),
+    ///     Board::new().to_translation_invariant_u64()
+    /// );
+    /// assert_eq!(board.to_translation_invariant_u64(), Board::new().to_u64());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_translation_invariant_u64(&self) -> u64 {
+        let Rectangle { hmin, vmin, .. } = self.minimum_rectangle();
+        let idx_shift = (hmin + 4 * vmin) as u64;
+
+        let mut hash = self.to_u64();
+        for ishift in 0..12 {
+            if hash & (1_u64 << (48 + ishift)) != 0 {
+                hash -= idx_shift << (4 * ishift);
+            }
+        }
+        hash
+    }
+
+    /// Returns the board transformed by `sym`.
+    ///
+    /// The 4x4 field is rotated or reflected around its center.
+    /// See [`Symmetry`] for details.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::str::FromStr;
+    /// use tokyodoves::{BoardBuilder, Symmetry};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let board = BoardBuilder::from_str("bB;A")?.build()?;
+    /// let rotated = board.transform(Symmetry::Rotate90);
+    /// assert_eq!(rotated, BoardBuilder::from_str("--Ab;---B")?.build()?);
+    /// assert_eq!(rotated.transform(Symmetry::Rotate270), board);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn transform(&self, sym: Symmetry) -> Board {
+        let matrix = self.to_4x4_matrix();
+        let mut transformed: [[Option<(Color, Dove)>; 4]; 4] = Default::default();
+        for (v, row) in matrix.iter().enumerate() {
+            for (h, cell) in row.iter().enumerate() {
+                let (tv, th) = sym.map_square(v, h);
+                transformed[tv][th] = *cell;
+            }
+        }
+        BoardBuilder::try_from_4x4_matrix(transformed)
+            .expect("transformed matrix must not have duplicated doves")
+            .build_unchecked()
+    }
+
+    /// Returns the canonical form of the board with `next_player` to move.
+    ///
+    /// All boards equivalent under swapping colors, rotations, reflections and translations
+    /// have the same canonical form, which is obtained as follows:
+    /// 1. Swap colors if `next_player` is green, so that red moves next.
+    /// 2. Transform the board by the [`Symmetry`]
+    ///     that minimizes [`to_translation_invariant_u64`](`Self::to_translation_invariant_u64`).
+    /// 3. Translate the board so that doves are as close to the top-left corner as possible.
+    ///
+    /// The returned tuple consists of the canonical board, the symmetry in step 2
+    /// and whether colors are swapped in step 1.
+    /// The [`to_u64`](`Self::to_u64`) of the canonical board coincides with
+    /// [`to_invariant_u64`](`Self::to_invariant_u64`) of the original board.
+    ///
+    /// Actions found on the canonical board (for red) can be mapped back onto the original board
+    /// by [`Action::transform`] with the inverse symmetry
+    /// and [`Action::swap_color`] if colors are swapped.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::str::FromStr;
+    /// use tokyodoves::{ActionContainer, BoardBuilder, Color};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let board = BoardBuilder::from_str("; b; BHy")?.build()?;
+    /// let (canonical, sym, color_swapped) = board.canonical(Color::Green);
+    /// assert!(color_swapped);
+    /// assert_eq!(canonical.to_u64(), board.to_invariant_u64(Color::Green));
+    ///
+    /// // Map an action on the canonical board back onto the original board
+    /// let action = canonical.legal_actions(Color::Red, true, true, true)[0];
+    /// let mut original = action.transform(sym.inverse());
+    /// original.swap_color();
+    /// assert!(board.legal_actions(Color::Green, true, true, true).contains(original));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn canonical(&self, next_player: Color) -> (Board, Symmetry, bool) {
+        let mut board = *self;
+        let color_swapped = matches!(next_player, Color::Green);
+        if color_swapped {
+            board.swap_color();
+        }
+        let (hash, sym) = Symmetry::iter()
+            .map(|sym| (board.transform(sym).to_translation_invariant_u64(), sym))
+            .min_by_key(|(hash, _)| *hash)
+            .expect("symmetries must not be empty");
+        let canonical = BoardBuilder::from_u64(hash).build_unchecked();
+        (canonical, sym, color_swapped)
+    }
+
+    /// Returns a Zobrist hash of the board.
+    ///
+    /// The hash is updated incrementally whenever an action is performed,
+    /// so this method is much cheaper than [`to_u64`](`Self::to_u64`).
+    /// Boards equal to each other have the same hash
+    /// (but different boards may have the same hash with a tiny probability).
+    /// The [`Hash`](`std::hash::Hash`) trait on [`Board`] is implemented by this hash.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::str::FromStr;
+    /// use tokyodoves::{Action, Board, BoardBuilder, Color, Dove, Shift};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut board = Board::new();
+    /// board.perform(Action::Put(Color::Red, Dove::A, Shift::new(0, 1)))?;
+    /// let expected = BoardBuilder::from_str("b;BA")?.build()?;
+    /// assert_eq!(board.zobrist_hash(), expected.zobrist_hash());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn zobrist_hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// Returns a Zobrist hash of the board
+    /// invariant under reflection, rotation and translation transformations.
+    ///
+    /// Hashes of the board under all 8 symmetries are updated incrementally
+    /// whenever an action is performed in the same way as [`zobrist_hash`](`Self::zobrist_hash`),
+    /// so this method is much cheaper than [`to_invariant_u64`](`Self::to_invariant_u64`).
+    /// Boards for which [`to_invariant_u64`](`Self::to_invariant_u64`) returns the same value
+    /// have the same hash
+    /// (but different boards may have the same hash with a tiny probability).
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::str::FromStr;
+    /// use tokyodoves::{BoardBuilder, Color};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let board1 = BoardBuilder::from_str("bB; H; y")?.build()?;
+    /// let board2 = BoardBuilder::from_str("; b; BHy")?.build()?;
+    /// let board3 = BoardBuilder::from_str("; B; bhY")?.build()?;
+    /// let hash = board1.invariant_zobrist_hash(Color::Red);
+    /// assert_eq!(hash, board2.invariant_zobrist_hash(Color::Red));
+    /// assert_eq!(hash, board3.invariant_zobrist_hash(Color::Green));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn invariant_zobrist_hash(&self, next_player: Color) -> u64 {
+        zobrist::invariant_hash(
+            &self.symmetric_hashes,
+            &self.minimum_rectangle(),
+            next_player,
+        )
+    }
+
     /// Returns 4x4 matrix (array of array) representing the board.
     ///
     /// # Examples
@@ -1705,6 +2293,21 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_legal_actions_bwd_capacity() {
+        use std::str::FromStr;
+        // Green has 104 backward actions on this reachable board
+        let board = BoardBuilder::from_str("T b ; B  ;A Y ;")
+            .unwrap()
+            .build()
+            .unwrap();
+        let actions = board.legal_actions_bwd(Color::Green, true, true, true);
+        assert_eq!(actions.len(), 104);
+        for a in actions.iter() {
+            assert!(board.check_action_bwd(*a).is_ok());
+        }
+    }
+
     #[test]
     fn test_strange_actions() {
         let num_turns = 10_000;
@@ -1833,4 +2436,256 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_transform_consistency() {
+        let num_turns = 2_000;
+        for (board, action, player) in RandomPlayIter::new().take(num_turns) {
+            let actions = board.legal_actions(player, true, true, true);
+            let next_board = board.perform_unchecked_copied(action);
+            for sym in Symmetry::iter() {
+                let transformed = board.transform(sym);
+                assert_eq!(transformed.transform(sym.inverse()), board);
+                assert_eq!(
+                    transformed.to_invariant_u64(player),
+                    board.to_invariant_u64(player)
+                );
+
+                // Legal actions are mapped bijectively
+                let transformed_actions = transformed.legal_actions(player, true, true, true);
+                assert_eq!(transformed_actions.len(), actions.len());
+                for a in actions.iter() {
+                    assert!(transformed_actions.contains(a.transform(sym)));
+                }
+
+                // Performing actions commutes with transformations up to translation
+                let next_transformed = transformed.perform_unchecked_copied(action.transform(sym));
+                assert_eq!(
+                    next_transformed.to_translation_invariant_u64(),
+                    next_board.transform(sym).to_translation_invariant_u64()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_canonical() {
+        let num_turns = 2_000;
+        for (board, action, player) in RandomPlayIter::new().take(num_turns) {
+            let (canonical, sym, color_swapped) = board.canonical(player);
+            assert_eq!(canonical.to_u64(), board.to_invariant_u64(player));
+            assert_eq!(color_swapped, player == Color::Green);
+
+            let mut swapped = board;
+            if color_swapped {
+                swapped.swap_color();
+            }
+            assert_eq!(
+                swapped.transform(sym).to_translation_invariant_u64(),
+                canonical.to_u64()
+            );
+
+            // Actions are mapped onto the canonical board and back
+            let mut canonical_action = action.transform(sym);
+            if color_swapped {
+                canonical_action.swap_color();
+            }
+            assert!(canonical
+                .legal_actions(Color::Red, true, true, true)
+                .contains(canonical_action));
+            let mut restored = canonical_action.transform(sym.inverse());
+            if color_swapped {
+                restored.swap_color();
+            }
+            assert_eq!(restored, action);
+
+            for other in Symmetry::iter() {
+                assert_eq!(board.transform(other).canonical(player).0, canonical);
+            }
+        }
+    }
+
+    #[test]
+    fn test_legal_actions_unique() {
+        let num_turns = 2_000;
+        let mut num_symmetric = 0;
+        for (board, _, player) in RandomPlayIter::new().take(num_turns) {
+            let key = board.to_translation_invariant_u64();
+            let is_symmetric = Symmetry::iter()
+                .skip(1)
+                .any(|sym| board.transform(sym).to_translation_invariant_u64() == key);
+            #[cfg(feature = "analysis")]
+            assert_eq!(board.is_symmetric(), is_symmetric);
+            if is_symmetric {
+                num_symmetric += 1;
+            }
+
+            let actions = board.legal_actions(player, true, true, true);
+            let (unique_actions, map) =
+                board.legal_actions_unique_with_map(player, true, true, true);
+            let unique_vec: Vec<Action> = unique_actions.iter().copied().collect();
+            let unique_vec2: Vec<Action> = board
+                .legal_actions_unique(player, true, true, true)
+                .into_iter()
+                .collect();
+            assert_eq!(unique_vec, unique_vec2);
+            assert_eq!(map.len(), actions.len());
+            let invariant = |action: Action| {
+                board
+                    .perform_unchecked_copied(action)
+                    .to_invariant_u64(!player)
+            };
+            for action in actions {
+                let representative = map[&action];
+                assert!(unique_actions.contains(representative));
+                assert_eq!(invariant(action), invariant(representative));
+            }
+            let keys: std::collections::HashSet<u64> =
+                unique_actions.iter().map(|a| invariant(*a)).collect();
+            assert_eq!(keys.len(), unique_actions.len());
+        }
+        assert!(num_symmetric > 0);
+    }
+
+    #[test]
+    fn test_winning_actions() {
+        let num_turns = 20_000;
+        let mut num_found = 0;
+        for (board, _, player) in RandomPlayIter::new().take(num_turns) {
+            for includes_both in [false, true] {
+                let mut expected: Vec<Action> = board
+                    .legal_actions(player, true, true, true)
+                    .into_iter()
+                    .filter(
+                        |a| match board.perform_unchecked_copied(*a).surrounded_status() {
+                            SurroundedStatus::OneSide(p) => p != player,
+                            SurroundedStatus::Both => includes_both,
+                            SurroundedStatus::None => false,
+                        },
+                    )
+                    .collect();
+                let mut actions: Vec<Action> = board
+                    .surrounding_actions(player, includes_both)
+                    .into_iter()
+                    .collect();
+                expected.sort_by_key(|a| format!("{a:?}"));
+                actions.sort_by_key(|a| format!("{a:?}"));
+                assert_eq!(actions, expected, "{board:?}");
+                num_found += actions.len();
+            }
+            let winning: Vec<Action> = board.winning_actions(!player).into_iter().collect();
+            let threats: Vec<Action> = board.threats(player).into_iter().collect();
+            assert_eq!(winning, threats);
+        }
+        assert!(num_found > 0);
+    }
+
+    #[test]
+    fn test_symmetry_group() {
+        for a in Symmetry::iter() {
+            assert_eq!(a.then(a.inverse()), Symmetry::Identity);
+            for b in Symmetry::iter() {
+                let shift = Shift::new(2, -1);
+                assert_eq!(shift.transform(a.then(b)), shift.transform(a).transform(b));
+            }
+        }
+    }
+
+    #[test]
+    fn test_zobrist_hash_consistency() {
+        let num_turns = 10_000;
+        let mut invariant_map = std::collections::HashMap::new();
+        for (board, _, _) in RandomPlayIter::new().take(num_turns) {
+            let board2 = BoardBuilder::from_u64(board.to_u64()).build_unchecked();
+            assert_eq!(board.zobrist_hash(), board2.zobrist_hash());
+            for player in Color::iter() {
+                let invariant_board =
+                    BoardBuilder::from_u64(board.to_invariant_u64(player)).build_unchecked();
+                assert_eq!(
+                    board.invariant_zobrist_hash(player),
+                    invariant_board.invariant_zobrist_hash(Color::Red),
+                );
+                // No collision is expected among a small number of boards
+                let key = board.to_invariant_u64(player);
+                let hash = board.invariant_zobrist_hash(player);
+                assert_eq!(*invariant_map.entry(hash).or_insert(key), key);
+            }
+        }
+        assert!(invariant_map.len() > num_turns / 10);
+    }
+
+    #[test]
+    fn test_zobrist_hash_consistency_bwd() {
+        let mut n = 0;
+        let mut board = Board::new();
+        let mut player = Color::Red;
+        for _ in 0..10_000 {
+            n = RandomPlayIter::random_num(n);
+            let actions = board.legal_actions_bwd(player, true, true, true);
+            board.perform_bwd(actions[n % actions.len()]).unwrap();
+            if !matches!(board.surrounded_status(), SurroundedStatus::None) {
+                board = Board::new();
+                player = Color::Red;
+                continue;
+            }
+            player = !player;
+
+            let board2 = BoardBuilder::from_u64(board.to_u64()).build_unchecked();
+            assert_eq!(board.zobrist_hash(), board2.zobrist_hash());
+            for player in Color::iter() {
+                assert_eq!(
+                    board.invariant_zobrist_hash(player),
+                    board2.invariant_zobrist_hash(player),
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_make_unmake() {
+        let exact_state = |board: &Board| {
+            let positions: Vec<u64> = Color::iter()
+                .flat_map(|c| Dove::iter().map(move |d| (c, d)))
+                .map(|(c, d)| *board.positions.position_of(c, d))
+                .collect();
+            (
+                board.viewer.status(),
+                positions,
+                board.zobrist_hash(),
+                board.symmetric_hashes,
+            )
+        };
+        for (mut board, _, player) in RandomPlayIter::new().take(3_000) {
+            let original = exact_state(&board);
+            let fwd = board.legal_actions(player, true, true, true);
+            let bwd = board.legal_actions_bwd(player, true, true, true);
+            for action in fwd.into_iter().chain(bwd) {
+                let expected = board.perform_unchecked_copied(action);
+                let token = board.make(action);
+                assert_eq!(exact_state(&board), exact_state(&expected));
+                board.unmake(token);
+                assert_eq!(exact_state(&board), original);
+            }
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        for (board, action, _) in RandomPlayIter::new().take(1_000) {
+            let json = serde_json::to_string(&board).unwrap();
+            assert_eq!(json, format!("{:?}", board.to_simple_string(' ', ";")));
+            assert_eq!(serde_json::from_str::<Board>(&json).unwrap(), board);
+            let bytes = bincode::serialize(&board).unwrap();
+            assert_eq!(bytes.len(), 8);
+            assert_eq!(bincode::deserialize::<Board>(&bytes).unwrap(), board);
+
+            let json = serde_json::to_string(&action).unwrap();
+            assert_eq!(serde_json::from_str::<Action>(&json).unwrap(), action);
+        }
+        let json = serde_json::to_string(&Action::Move(Color::Red, Dove::A, Shift::new(1, -1)));
+        assert_eq!(json.unwrap(), r#"{"Move":["Red","A",{"dh":-1,"dv":1}]}"#);
+        // Two red bosses
+        assert!(serde_json::from_str::<Board>(r#""B;B""#).is_err());
+    }
 }
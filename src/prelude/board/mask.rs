@@ -702,6 +702,12 @@ impl MaskViewer {
         Self { status: 0 }
     }
 
+    /// Returns the index of the current [`BitMask`], i.e.,
+    /// how many bits the view is rotated from the initial one.
+    pub(crate) fn status(&self) -> usize {
+        self.status
+    }
+
     pub fn view_mask(&self) -> &BitMask {
         // safety is guaranteed because self.status can be changed only via do_shift
         unsafe { MASKS.get_unchecked(self.status) }
@@ -0,0 +1,213 @@
+//! Zobrist hashes of [`Board`](`crate::Board`).
+//!
+//! Positions of doves are bits of `u64` on which the 4x4 field is viewed by [`MaskViewer`].
+//! The key of a dove at a bit is the key of the dove rotated by the index of the bit
+//! relative to the viewer.
+//! Since rotation commutes with XOR,
+//! shifting the viewer corresponds to rotating the whole hash,
+//! so that the hash is determined only by the positions in the 4x4 field
+//! (in other words, by [`Board::to_u64`](`crate::Board::to_u64`)).
+//!
+//! Invariant hashes are calculated from 8 hashes of the positions mapped by all symmetries,
+//! which are updated incrementally in the same way.
+//! The key of a green dove is the key of the red one rotated by 32 bits,
+//! so that swapping colors corresponds to rotating the hash by 32 bits
+//! once the hash is normalized so that the minimum rectangle containing all doves
+//! is placed at the top-left corner.
+//! The minimum of the normalized hashes is invariant
+//! under reflection, rotation and translation.
+
+use strum::IntoEnumIterator;
+
+use crate::prelude::{
+    board::{
+        mask::{MaskViewer, Rectangle},
+        position::ColorDovePositions,
+    },
+    pieces::{color_to_index, dove_to_index, Color, Dove},
+};
+
+const fn splitmix64(state: u64) -> (u64, u64) {
+    let state = state.wrapping_add(0x9e3779b97f4a7c15);
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    (state, z ^ (z >> 31))
+}
+
+/// Keys indexed by color and dove
+const KEYS: [[u64; 6]; 2] = {
+    let mut keys = [[0; 6]; 2];
+    let mut state = 0x7d0c_1d0e_5a11_ce11;
+    let mut i = 0;
+    while i < 12 {
+        let (next_state, key) = splitmix64(state);
+        state = next_state;
+        keys[i / 6][i % 6] = key;
+        i += 1;
+    }
+    keys
+};
+
+/// Keys for symmetric hashes indexed by dove
+const SYMMETRIC_KEYS: [u64; 6] = {
+    let mut keys = [0; 6];
+    let mut state = 0x1e55_ba55_d0fe_5eed;
+    let mut i = 0;
+    while i < 6 {
+        let (next_state, key) = splitmix64(state);
+        state = next_state;
+        keys[i] = key;
+        i += 1;
+    }
+    keys
+};
+
+/// Hashes of the positions mapped by 8 symmetries
+pub(crate) type SymmetricHashes = [u64; 8];
+
+/// Maps the displacement `(v, h)` by the symmetry of index `sym`.
+const fn image(sym: usize, v: i32, h: i32) -> (i32, i32) {
+    match sym {
+        0 => (v, h),
+        1 => (v, -h),
+        2 => (-v, h),
+        3 => (-v, -h),
+        4 => (h, v),
+        5 => (h, -v),
+        6 => (-h, v),
+        _ => (-h, -v),
+    }
+}
+
+/// Returns the index of the bit displaced by `(v, h)` from the bit of index zero.
+fn index_of(v: i32, h: i32) -> u32 {
+    (8 * v + h).rem_euclid(64) as u32
+}
+
+/// Returns the index of `pos` relative to the viewer.
+fn relative_index(pos: u64, viewer: &MaskViewer) -> usize {
+    (pos.trailing_zeros() as usize + 64 - viewer.status()) % 64
+}
+
+fn key(color: Color, dove: Dove, pos: u64, viewer: &MaskViewer) -> u64 {
+    let key = KEYS[color_to_index(color)][dove_to_index(dove)];
+    key.rotate_left(relative_index(pos, viewer) as u32)
+}
+
+/// Calculates the hash from scratch.
+pub(crate) fn hash(viewer: &MaskViewer, positions: &ColorDovePositions) -> u64 {
+    let mut hash = 0;
+    for c in [Color::Red, Color::Green] {
+        for d in Dove::iter() {
+            let pos = *positions.position_of(c, d);
+            if pos != 0 {
+                hash ^= key(c, d, pos, viewer);
+            }
+        }
+    }
+    hash
+}
+
+/// Updates `hash` after the viewer shifted from `old_status`.
+pub(crate) fn shift_view(hash: u64, old_status: usize, viewer: &MaskViewer) -> u64 {
+    let diff = (viewer.status() + 64 - old_status) % 64;
+    hash.rotate_right(diff as u32)
+}
+
+/// Updates `hash` after the dove moved from `old_pos` to `new_pos`,
+/// where zero means that the dove is in the hand.
+///
+/// `viewer` must be the one after the change.
+pub(crate) fn move_dove(
+    hash: u64,
+    color: Color,
+    dove: Dove,
+    old_pos: u64,
+    new_pos: u64,
+    viewer: &MaskViewer,
+) -> u64 {
+    [old_pos, new_pos]
+        .into_iter()
+        .filter(|pos| *pos != 0)
+        .fold(hash, |hash, pos| hash ^ key(color, dove, pos, viewer))
+}
+
+/// Toggles the dove at `pos` in the field of `viewer` in symmetric `hashes`.
+pub(crate) fn toggle_symmetric(
+    hashes: &mut SymmetricHashes,
+    color: Color,
+    dove: Dove,
+    pos: u64,
+    viewer: &MaskViewer,
+) {
+    let key = match color {
+        Color::Red => SYMMETRIC_KEYS[dove_to_index(dove)],
+        Color::Green => SYMMETRIC_KEYS[dove_to_index(dove)].rotate_left(32),
+    };
+    let idx = relative_index(pos, viewer) as i32;
+    let (v, h) = (idx / 8, idx % 8);
+    for (sym, hash) in hashes.iter_mut().enumerate() {
+        let (v, h) = image(sym, v, h);
+        *hash ^= key.rotate_left(index_of(v, h));
+    }
+}
+
+/// Calculates symmetric hashes from scratch.
+pub(crate) fn symmetric_hashes(
+    viewer: &MaskViewer,
+    positions: &ColorDovePositions,
+) -> SymmetricHashes {
+    let mut hashes = [0; 8];
+    for c in [Color::Red, Color::Green] {
+        for d in Dove::iter() {
+            let pos = *positions.position_of(c, d);
+            if pos != 0 {
+                toggle_symmetric(&mut hashes, c, d, pos, viewer);
+            }
+        }
+    }
+    hashes
+}
+
+/// Updates symmetric `hashes` after the viewer shifted from `old_status`.
+pub(crate) fn shift_view_symmetric(
+    hashes: &mut SymmetricHashes,
+    old_status: usize,
+    viewer: &MaskViewer,
+) {
+    // The difference of indices is 8 * (vertical) + (horizontal),
+    // where both components range from -1 to 1
+    let diff = (viewer.status() as i32 - old_status as i32 + 64) % 64;
+    let diff = if diff >= 32 { diff - 64 } else { diff };
+    let v = (diff + 3).div_euclid(8);
+    let h = diff - 8 * v;
+    for (sym, hash) in hashes.iter_mut().enumerate() {
+        let (v, h) = image(sym, v, h);
+        *hash = hash.rotate_right(index_of(v, h));
+    }
+}
+
+/// Calculates the invariant hash from symmetric `hashes`,
+/// where `rectangle` is the minimum rectangle containing all doves.
+pub(crate) fn invariant_hash(
+    hashes: &SymmetricHashes,
+    rectangle: &Rectangle,
+    next_player: Color,
+) -> u64 {
+    let (vmin, vmax) = (rectangle.vmin as i32, rectangle.vmax as i32);
+    let (hmin, hmax) = (rectangle.hmin as i32, rectangle.hmax as i32);
+    let mut invariant = u64::MAX;
+    for (sym, hash) in hashes.iter().enumerate() {
+        // Move the top-left corner of the mapped rectangle to the index zero
+        let (v1, h1) = image(sym, vmin, hmin);
+        let (v2, h2) = image(sym, vmax, hmax);
+        let hash = hash.rotate_right(index_of(v1.min(v2), h1.min(h2)));
+        let hash = match next_player {
+            Color::Red => hash,
+            Color::Green => hash.rotate_left(32),
+        };
+        invariant = invariant.min(hash);
+    }
+    invariant
+}
@@ -4,6 +4,7 @@ use strum_macros::EnumIter;
 ///
 /// It is just like black and white in chess.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Color {
     Red,
     Green,
@@ -28,6 +29,7 @@ impl std::ops::Not for Color {
 
 /// Six types of doves.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Dove {
     /// Represents **B**oss-hato (Boss Dove in English),
     /// which can move to adjacent squares in all eight directions,
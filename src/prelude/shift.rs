@@ -1,3 +1,5 @@
+use crate::prelude::symmetry::Symmetry;
+
 /// A struct representing difference between two squares.
 ///
 /// It has two data `dh`, i.e. the **h**orizonal difference,
@@ -21,6 +23,7 @@
 /// an element in a two-dimensional Affine space with integral coordinates
 /// without scalar multiplication.
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Shift {
     /// Horizontal shift. Positive (or negative) direction is on right (or left).
     pub(crate) dh: i8,
@@ -59,6 +62,23 @@ impl Shift {
     pub fn dv_mut(&mut self) -> &mut i8 {
         &mut self.dv
     }
+
+    /// Returns the shift transformed by `sym`.
+    ///
+    /// See [`Symmetry`] for details.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tokyodoves::{Shift, Symmetry};
+    ///
+    /// let shift = Shift::new(-2, 1);
+    /// assert_eq!(shift.transform(Symmetry::Rotate180), Shift::new(2, -1));
+    /// assert_eq!(shift.transform(Symmetry::FlipDiagonal), Shift::new(1, -2));
+    /// ```
+    pub fn transform(&self, sym: Symmetry) -> Shift {
+        let (dv, dh) = sym.map(self.dv, self.dh);
+        Shift::new(dv, dh)
+    }
 }
 
 impl std::ops::Add for Shift {
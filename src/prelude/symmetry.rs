@@ -0,0 +1,113 @@
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+/// Eight symmetries of a square, i.e., rotations and reflections.
+///
+/// Boards transformed by any symmetry have effectively the same value in the game
+/// because the movements of all doves are symmetric.
+/// A symmetry acts on
+/// [`Board`](`crate::Board`) by [`Board::transform`](`crate::Board::transform`),
+/// [`Shift`](`crate::Shift`) by [`Shift::transform`](`crate::Shift::transform`) and
+/// [`Action`](`crate::Action`) by [`Action::transform`](`crate::Action::transform`)
+/// consistently, that is, performing an action on a board and then transforming the board
+/// results in the same board as performing the transformed action on the transformed board
+/// (up to translation).
+///
+/// Rotations are clockwise.
+/// For example, [`Rotate90`](`Symmetry::Rotate90`) maps the right (`Shift::new(0, 1)`)
+/// to the bottom (`Shift::new(1, 0)`).
+///
+/// # Examples
+/// ```rust
+/// use tokyodoves::{Shift, Symmetry};
+///
+/// let shift = Shift::new(0, 1);
+/// assert_eq!(shift.transform(Symmetry::Rotate90), Shift::new(1, 0));
+/// assert_eq!(shift.transform(Symmetry::FlipHorizontal), Shift::new(0, -1));
+///
+/// let sym = Symmetry::Rotate90;
+/// assert_eq!(sym.then(sym.inverse()), Symmetry::Identity);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Symmetry {
+    /// Does nothing
+    Identity,
+    /// Rotates by 90 degrees clockwise
+    Rotate90,
+    /// Rotates by 180 degrees
+    Rotate180,
+    /// Rotates by 270 degrees clockwise (90 degrees counterclockwise)
+    Rotate270,
+    /// Swaps the left and the right
+    FlipHorizontal,
+    /// Swaps the top and the bottom
+    FlipVertical,
+    /// Reflects in the diagonal from the top-left to the bottom-right
+    FlipDiagonal,
+    /// Reflects in the diagonal from the top-right to the bottom-left
+    FlipAntiDiagonal,
+}
+
+impl Symmetry {
+    /// Maps a vector `(dv, dh)` by the symmetry.
+    pub(crate) fn map(&self, dv: i8, dh: i8) -> (i8, i8) {
+        use Symmetry::*;
+        match self {
+            Identity => (dv, dh),
+            Rotate90 => (dh, -dv),
+            Rotate180 => (-dv, -dh),
+            Rotate270 => (-dh, dv),
+            FlipHorizontal => (dv, -dh),
+            FlipVertical => (-dv, dh),
+            FlipDiagonal => (dh, dv),
+            FlipAntiDiagonal => (-dh, -dv),
+        }
+    }
+
+    /// Maps a square `(v, h)` in 4x4 field by the symmetry around the center of the field.
+    pub(crate) fn map_square(&self, v: usize, h: usize) -> (usize, usize) {
+        // Coordinates doubled so that the center of the field is the origin
+        let (dv, dh) = self.map(2 * v as i8 - 3, 2 * h as i8 - 3);
+        (((dv + 3) / 2) as usize, ((dh + 3) / 2) as usize)
+    }
+
+    /// Returns the symmetry that undoes `self`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tokyodoves::Symmetry;
+    ///
+    /// assert_eq!(Symmetry::Rotate90.inverse(), Symmetry::Rotate270);
+    /// assert_eq!(Symmetry::FlipDiagonal.inverse(), Symmetry::FlipDiagonal);
+    /// ```
+    pub fn inverse(&self) -> Symmetry {
+        use Symmetry::*;
+        match self {
+            Rotate90 => Rotate270,
+            Rotate270 => Rotate90,
+            sym => *sym,
+        }
+    }
+
+    /// Returns the symmetry that applies `self` first and `other` next.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tokyodoves::Symmetry;
+    ///
+    /// let sym = Symmetry::Rotate90.then(Symmetry::Rotate180);
+    /// assert_eq!(sym, Symmetry::Rotate270);
+    /// let sym = Symmetry::FlipHorizontal.then(Symmetry::FlipVertical);
+    /// assert_eq!(sym, Symmetry::Rotate180);
+    /// ```
+    pub fn then(&self, other: Symmetry) -> Symmetry {
+        // A symmetry is determined by the images of two independent vectors
+        let images = |sym: Symmetry| (sym.map(1, 0), sym.map(0, 1));
+        let ((v0, h0), (v1, h1)) = images(*self);
+        let expected = (other.map(v0, h0), other.map(v1, h1));
+        Symmetry::iter()
+            .find(|sym| images(*sym) == expected)
+            .expect("symmetries must be closed under composition")
+    }
+}